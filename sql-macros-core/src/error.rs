@@ -0,0 +1,271 @@
+use std::fmt;
+
+use sqlx::error::ErrorKind;
+
+use crate::TransactionError;
+
+/// Error of generated methods with `#[table(error = sql_macros::Error)]`,
+/// constraint violations are mapped from codes of Postgres, MySQL and SQLite
+#[derive(Debug)]
+pub enum Error {
+    /// Query returned no rows
+    NotFound,
+    UniqueViolation {
+        constraint: Option<String>,
+        /// Known for Postgres and SQLite
+        columns: Vec<String>,
+    },
+    ForeignKeyViolation {
+        constraint: Option<String>,
+    },
+    CheckViolation {
+        constraint: Option<String>,
+    },
+    /// Row was changed or removed by other transaction
+    Stale,
+    Database(sqlx::Error),
+}
+
+/// Columns of unique violation from message of database:
+/// Postgres `Key (email)=(a@x) already exists.`, SQLite `UNIQUE constraint failed: users.email`
+fn unique_columns(err: &dyn sqlx::error::DatabaseError) -> Vec<String> {
+    let pg_detail = err
+        .try_downcast_ref::<sqlx::postgres::PgDatabaseError>()
+        .and_then(|err| err.detail());
+    if let Some(columns) = pg_detail
+        .and_then(|detail| detail.strip_prefix("Key ("))
+        .and_then(|detail| detail.split_once(")="))
+        .map(|(columns, _)| columns)
+    {
+        return columns.split(", ").map(str::to_string).collect();
+    }
+    if let Some(columns) = err.message().strip_prefix("UNIQUE constraint failed: ") {
+        return columns
+            .split(", ")
+            .map(|column| column.rsplit('.').next().unwrap_or(column).to_string())
+            .collect();
+    }
+    vec![]
+}
+
+/// Constraint name, MySQL has it only in message `Duplicate entry 'a@x' for key 'users.email'`
+fn constraint(err: &dyn sqlx::error::DatabaseError) -> Option<String> {
+    err.constraint().map(str::to_string).or_else(|| {
+        err.message()
+            .rsplit_once("for key '")
+            .map(|(_, key)| key.trim_end_matches('\'').to_string())
+    })
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(err: sqlx::Error) -> Self {
+        let sqlx::Error::Database(db_err) = &err else {
+            return match err {
+                sqlx::Error::RowNotFound => Error::NotFound,
+                err => Error::Database(err),
+            };
+        };
+        match db_err.kind() {
+            ErrorKind::UniqueViolation => Error::UniqueViolation {
+                constraint: constraint(db_err.as_ref()),
+                columns: unique_columns(db_err.as_ref()),
+            },
+            ErrorKind::ForeignKeyViolation => Error::ForeignKeyViolation {
+                constraint: constraint(db_err.as_ref()),
+            },
+            ErrorKind::CheckViolation => Error::CheckViolation {
+                constraint: constraint(db_err.as_ref()),
+            },
+            _ => Error::Database(err),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "row not found"),
+            Error::UniqueViolation {
+                constraint,
+                columns,
+            } => write!(
+                f,
+                "unique violation of {} ({})",
+                constraint.as_deref().unwrap_or("constraint"),
+                columns.join(", ")
+            ),
+            Error::ForeignKeyViolation { constraint } => write!(
+                f,
+                "foreign key violation of {}",
+                constraint.as_deref().unwrap_or("constraint")
+            ),
+            Error::CheckViolation { constraint } => write!(
+                f,
+                "check violation of {}",
+                constraint.as_deref().unwrap_or("constraint")
+            ),
+            Error::Stale => write!(f, "row was changed by other transaction"),
+            Error::Database(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Database(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl TransactionError for Error {
+    fn is_serialization_failure(&self) -> bool {
+        matches!(self, Error::Database(err) if err.is_serialization_failure())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::error::Error as StdError;
+
+    use super::*;
+
+    /// Error of database which is not Postgres, like errors of SQLite and MySQL drivers
+    #[derive(Debug)]
+    struct DriverError {
+        message: &'static str,
+        code: Option<&'static str>,
+        constraint: Option<&'static str>,
+        kind: ErrorKind,
+    }
+
+    impl DriverError {
+        fn new(kind: ErrorKind, message: &'static str) -> Self {
+            Self {
+                message,
+                code: None,
+                constraint: None,
+                kind,
+            }
+        }
+
+        fn into_sqlx(self) -> sqlx::Error {
+            sqlx::Error::Database(Box::new(self))
+        }
+    }
+
+    impl fmt::Display for DriverError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.message)
+        }
+    }
+
+    impl StdError for DriverError {}
+
+    impl sqlx::error::DatabaseError for DriverError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            self.code.map(Cow::Borrowed)
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn kind(&self) -> ErrorKind {
+            match self.kind {
+                ErrorKind::UniqueViolation => ErrorKind::UniqueViolation,
+                ErrorKind::ForeignKeyViolation => ErrorKind::ForeignKeyViolation,
+                ErrorKind::CheckViolation => ErrorKind::CheckViolation,
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    #[test]
+    fn row_not_found_is_not_found() {
+        assert!(matches!(
+            Error::from(sqlx::Error::RowNotFound),
+            Error::NotFound
+        ));
+        assert!(matches!(
+            Error::from(sqlx::Error::PoolTimedOut),
+            Error::Database(sqlx::Error::PoolTimedOut)
+        ));
+    }
+
+    #[test]
+    fn sqlite_unique_violation_has_columns() {
+        let err = DriverError::new(
+            ErrorKind::UniqueViolation,
+            "UNIQUE constraint failed: users.org_id, users.email",
+        );
+        let Error::UniqueViolation {
+            constraint,
+            columns,
+        } = Error::from(err.into_sqlx())
+        else {
+            panic!("Expected unique violation");
+        };
+        assert_eq!(constraint, None);
+        assert_eq!(columns, vec!["org_id", "email"]);
+    }
+
+    #[test]
+    fn mysql_unique_violation_has_constraint_of_message() {
+        let err = DriverError::new(
+            ErrorKind::UniqueViolation,
+            "Duplicate entry 'a@x' for key 'users.email'",
+        );
+        let err = Error::from(err.into_sqlx());
+        assert_eq!(err.to_string(), "unique violation of users.email ()");
+    }
+
+    #[test]
+    fn constraint_of_driver_is_preferred() {
+        let err = DriverError {
+            constraint: Some("posts_user_id_fkey"),
+            ..DriverError::new(ErrorKind::ForeignKeyViolation, "violates foreign key")
+        };
+        assert!(matches!(
+            Error::from(err.into_sqlx()),
+            Error::ForeignKeyViolation { constraint: Some(constraint) } if constraint == "posts_user_id_fkey"
+        ));
+        let err = DriverError::new(ErrorKind::CheckViolation, "CHECK constraint failed: price");
+        assert!(matches!(
+            Error::from(err.into_sqlx()),
+            Error::CheckViolation { constraint: None }
+        ));
+    }
+
+    #[test]
+    fn serialization_failure_is_retried() {
+        let err = DriverError {
+            code: Some("40001"),
+            ..DriverError::new(ErrorKind::Other, "could not serialize access")
+        };
+        assert!(Error::from(err.into_sqlx()).is_serialization_failure());
+        let err = DriverError {
+            code: Some("23505"),
+            ..DriverError::new(ErrorKind::Other, "duplicate key")
+        };
+        assert!(!Error::from(err.into_sqlx()).is_serialization_failure());
+    }
+}
@@ -0,0 +1,183 @@
+use sqlx::{Encode, Postgres, QueryBuilder, Type};
+
+/// Condition of text column for `filter` of `#[derive(SqlFilter)]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StringFilter {
+    Eq(String),
+    Ne(String),
+    /// `LIKE '%value%'`, `%` and `_` of value are escaped
+    Contains(String),
+    /// `ILIKE '%value%'`
+    IContains(String),
+    StartsWith(String),
+    EndsWith(String),
+    In(Vec<String>),
+}
+
+/// Escape wildcards of `LIKE` pattern
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+impl StringFilter {
+    /// Push ` AND {column} ...` with bound value
+    pub fn push_sql(&self, column: &str, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push(format!(" AND {column} "));
+        match self {
+            StringFilter::Eq(value) => builder.push("= ").push_bind(value.clone()),
+            StringFilter::Ne(value) => builder.push("<> ").push_bind(value.clone()),
+            StringFilter::Contains(value) => builder
+                .push("LIKE ")
+                .push_bind(format!("%{}%", escape_like(value))),
+            StringFilter::IContains(value) => builder
+                .push("ILIKE ")
+                .push_bind(format!("%{}%", escape_like(value))),
+            StringFilter::StartsWith(value) => builder
+                .push("LIKE ")
+                .push_bind(format!("{}%", escape_like(value))),
+            StringFilter::EndsWith(value) => builder
+                .push("LIKE ")
+                .push_bind(format!("%{}", escape_like(value))),
+            StringFilter::In(values) => builder.push("= ANY(").push_bind(values.clone()).push(")"),
+        };
+    }
+}
+
+/// Bounds of number, date or time column, missing bound is not checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range<T> {
+    pub gt: Option<T>,
+    pub gte: Option<T>,
+    pub lt: Option<T>,
+    pub lte: Option<T>,
+}
+
+impl<T> Default for Range<T> {
+    fn default() -> Self {
+        Self {
+            gt: None,
+            gte: None,
+            lt: None,
+            lte: None,
+        }
+    }
+}
+
+impl<T> Range<T> {
+    /// `from <= column < to`
+    pub fn between(from: T, to: T) -> Self {
+        Self {
+            gte: Some(from),
+            lt: Some(to),
+            ..Default::default()
+        }
+    }
+
+    /// `from <= column`
+    pub fn since(from: T) -> Self {
+        Self {
+            gte: Some(from),
+            ..Default::default()
+        }
+    }
+
+    /// `column < to`
+    pub fn until(to: T) -> Self {
+        Self {
+            lt: Some(to),
+            ..Default::default()
+        }
+    }
+}
+
+impl<'args, T> Range<T>
+where
+    T: Encode<'args, Postgres> + Type<Postgres> + Clone + Send + 'args,
+{
+    /// Push ` AND {column} >= $1 AND ...` for each bound
+    pub fn push_sql(&self, column: &str, builder: &mut QueryBuilder<'args, Postgres>) {
+        for (operator, bound) in [
+            (">", &self.gt),
+            (">=", &self.gte),
+            ("<", &self.lt),
+            ("<=", &self.lte),
+        ] {
+            if let Some(bound) = bound {
+                builder
+                    .push(format!(" AND {column} {operator} "))
+                    .push_bind(bound.clone());
+            }
+        }
+    }
+}
+
+/// Sorting of runtime queries, column is checked by name of field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderBy {
+    pub column: String,
+    pub desc: bool,
+}
+
+impl OrderBy {
+    pub fn asc(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            desc: false,
+        }
+    }
+
+    pub fn desc(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            desc: true,
+        }
+    }
+}
+
+/// Push ` ORDER BY ...`, `columns` are pairs of field and sql column.
+///
+/// Return `sqlx::Error::ColumnNotFound` if column of order is not field
+pub fn push_order_by(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    order: &[OrderBy],
+    columns: &[(&str, &str)],
+) -> Result<(), sqlx::Error> {
+    for (index, order_by) in order.iter().enumerate() {
+        let (_, column) = columns
+            .iter()
+            .find(|(field, _)| *field == order_by.column)
+            .ok_or_else(|| sqlx::Error::ColumnNotFound(order_by.column.clone()))?;
+        let direction = if order_by.desc { "DESC" } else { "ASC" };
+        let separator = if index == 0 { " ORDER BY " } else { ", " };
+        builder.push(format!("{separator}{column} {direction}"));
+    }
+    Ok(())
+}
+
+/// `LIMIT` and `OFFSET` of runtime queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Page {
+    /// Page by number from 1
+    pub fn new(number: i64, size: i64) -> Self {
+        Self {
+            limit: size,
+            offset: (number.max(1) - 1) * size,
+        }
+    }
+
+    pub fn push_sql(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder
+            .push(" LIMIT ")
+            .push_bind(self.limit)
+            .push(" OFFSET ")
+            .push_bind(self.offset);
+    }
+}
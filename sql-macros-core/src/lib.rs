@@ -1,3 +1,31 @@
+mod error;
+mod filter;
+#[cfg(feature = "migrate")]
+mod migration;
+#[cfg(feature = "mock")]
+mod mock;
+mod query;
+mod repository;
+mod rls;
+mod schema;
+mod search;
+mod stream;
+mod transaction;
 mod types;
 
+pub use error::*;
+pub use filter::*;
+#[cfg(feature = "migrate")]
+pub use migration::*;
+#[cfg(feature = "mock")]
+pub use mock::*;
+pub use query::*;
+pub use repository::*;
+pub use rls::*;
+pub use schema::*;
+pub use search::*;
+pub use stream::*;
+pub use transaction::*;
 pub use types::*;
+
+pub use futures_core::Stream;
@@ -0,0 +1,581 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Column, Dialect, Schema};
+
+/// Statements to move database from snapshot to declared schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub statements: Vec<String>,
+    /// Declared schema, it should be stored as new snapshot after the migration is written
+    pub snapshot: Vec<Schema>,
+}
+
+impl Migration {
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    pub fn to_sql(&self) -> String {
+        self.statements
+            .iter()
+            .map(|statement| format!("{statement};\n"))
+            .collect()
+    }
+}
+
+/// Return error if dialect can't alter column
+fn alter_column(
+    old: &Column,
+    new: &Column,
+    table: &str,
+    dialect: Dialect,
+) -> io::Result<Vec<String>> {
+    let name = &new.name;
+    let type_changed = old.sql_type != new.sql_type;
+    let nullable_changed = old.nullable != new.nullable;
+    let default_changed = old.default != new.default;
+
+    if !type_changed && !nullable_changed && !default_changed {
+        return Ok(vec![]);
+    }
+
+    let statements = match dialect {
+        Dialect::Postgres => {
+            let mut statements = vec![];
+            if type_changed {
+                let sql_type = new.sql_type.to_sql(dialect);
+                statements.push(format!(
+                    "ALTER TABLE {table} ALTER COLUMN {name} TYPE {sql_type} USING {name}::{sql_type}"
+                ));
+            }
+            if nullable_changed {
+                let action = if new.nullable { "DROP" } else { "SET" };
+                statements.push(format!(
+                    "ALTER TABLE {table} ALTER COLUMN {name} {action} NOT NULL"
+                ));
+            }
+            if default_changed {
+                statements.push(match &new.default {
+                    Some(default) => {
+                        format!("ALTER TABLE {table} ALTER COLUMN {name} SET DEFAULT {default}")
+                    }
+                    None => format!("ALTER TABLE {table} ALTER COLUMN {name} DROP DEFAULT"),
+                });
+            }
+            statements
+        }
+        Dialect::MySql => {
+            // MODIFY COLUMN takes full definition, constraints are kept separately
+            let column = Column {
+                primary_key: false,
+                unique: false,
+                check: None,
+                ..new.clone()
+            };
+            vec![format!(
+                "ALTER TABLE {table} MODIFY COLUMN {}",
+                column.to_sql(dialect)
+            )]
+        }
+        Dialect::Sqlite => {
+            return Err(unsupported(format!(
+                "SQLite can't alter column {table}.{name}, recreate the table"
+            )));
+        }
+    };
+    Ok(statements)
+}
+
+/// Column added to existing table, `NOT NULL` column must have default for existing rows
+fn add_column(column: &Column, table: &str, dialect: Dialect) -> io::Result<String> {
+    if !column.nullable && column.default.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Column {table}.{} is added as NOT NULL without default, add #[table(default = ..)] or make it Option",
+                column.name
+            ),
+        ));
+    }
+    // Primary key is added by alter constraints
+    Ok(format!(
+        "ALTER TABLE {table} ADD COLUMN {}",
+        column.definition(dialect, false)
+    ))
+}
+
+fn unsupported(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, message)
+}
+
+/// Constraints `PRIMARY KEY`, `UNIQUE` and `CHECK` of changed table, Postgres names them
+/// `{table}_pkey`, `{table}_{column}_key` and `{table}_{column}_check`.
+///
+/// Return error if dialect can't change constraint
+fn alter_constraints(old: &Schema, new: &Schema, dialect: Dialect) -> io::Result<Vec<String>> {
+    let table = &new.table;
+    let mut statements = vec![];
+
+    let (old_primary_key, new_primary_key) = (old.primary_key(), new.primary_key());
+    if old_primary_key != new_primary_key {
+        match dialect {
+            Dialect::Postgres if !old_primary_key.is_empty() => {
+                statements.push(format!("ALTER TABLE {table} DROP CONSTRAINT {table}_pkey"))
+            }
+            Dialect::MySql if !old_primary_key.is_empty() => {
+                statements.push(format!("ALTER TABLE {table} DROP PRIMARY KEY"))
+            }
+            Dialect::Sqlite => {
+                return Err(unsupported(format!(
+                    "SQLite can't change primary key of {table}, recreate the table"
+                )));
+            }
+            _ => {}
+        }
+        if !new_primary_key.is_empty() {
+            statements.push(format!(
+                "ALTER TABLE {table} ADD PRIMARY KEY ({})",
+                new_primary_key.join(", ")
+            ));
+        }
+    }
+
+    for column in &new.columns {
+        let Some(old_column) = old.column(&column.name) else {
+            continue;
+        };
+        let name = &column.name;
+        if old_column.unique != column.unique {
+            statements.push(match (dialect, column.unique) {
+                (Dialect::Postgres, true) => {
+                    format!("ALTER TABLE {table} ADD CONSTRAINT {table}_{name}_key UNIQUE ({name})")
+                }
+                (Dialect::Postgres, false) => {
+                    format!("ALTER TABLE {table} DROP CONSTRAINT {table}_{name}_key")
+                }
+                (Dialect::MySql, true) => format!("ALTER TABLE {table} ADD UNIQUE ({name})"),
+                (Dialect::MySql, false) => format!("ALTER TABLE {table} DROP INDEX {name}"),
+                (Dialect::Sqlite, _) => {
+                    return Err(unsupported(format!(
+                        "SQLite can't change unique of {table}.{name}, recreate the table"
+                    )));
+                }
+            });
+        }
+        if old_column.check != column.check {
+            // MySQL generates names of checks, they can't be dropped by name of column
+            if dialect != Dialect::Postgres {
+                return Err(unsupported(format!(
+                    "{dialect:?} can't change check of {table}.{name}, write migration by hand"
+                )));
+            }
+            if old_column.check.is_some() {
+                statements.push(format!(
+                    "ALTER TABLE {table} DROP CONSTRAINT {table}_{name}_check"
+                ));
+            }
+            if let Some(check) = &column.check {
+                statements.push(format!(
+                    "ALTER TABLE {table} ADD CONSTRAINT {table}_{name}_check CHECK ({check})"
+                ));
+            }
+        }
+    }
+    Ok(statements)
+}
+
+/// Compare snapshot with declared schemas and return ordered statements:
+/// create tables, add columns, alter columns, alter constraints, drop indexes, drop columns,
+/// create indexes, drop tables.
+///
+/// Changed index is dropped and created again, return error if dialect can't apply a change
+pub fn diff_schemas<'a>(
+    snapshot: &'a [Schema],
+    declared: &'a [Schema],
+    dialect: Dialect,
+) -> io::Result<Vec<String>> {
+    let find = |schemas: &'a [Schema], table: &str| -> Option<&'a Schema> {
+        schemas.iter().find(|schema| schema.table == table)
+    };
+
+    let mut create_tables = vec![];
+    let mut add_columns = vec![];
+    let mut alter_columns = vec![];
+    let mut alter_constraints_list = vec![];
+    let mut drop_indexes = vec![];
+    let mut drop_columns = vec![];
+    let mut create_indexes = vec![];
+    let mut drop_tables = vec![];
+
+    for schema in declared {
+        let Some(old) = find(snapshot, &schema.table) else {
+            create_tables.extend(schema.ddl(dialect)?);
+            continue;
+        };
+        let table = &schema.table;
+        for column in &schema.columns {
+            match old.column(&column.name) {
+                Some(old_column) => {
+                    alter_columns.extend(alter_column(old_column, column, table, dialect)?)
+                }
+                None => add_columns.push(add_column(column, table, dialect)?),
+            }
+        }
+        alter_constraints_list.extend(alter_constraints(old, schema, dialect)?);
+        for column in &old.columns {
+            if schema.column(&column.name).is_none() {
+                drop_columns.push(format!("ALTER TABLE {table} DROP COLUMN {}", column.name));
+            }
+        }
+        for index in &old.indexes {
+            if !schema.indexes.contains(index) {
+                drop_indexes.push(index.drop_index_sql(table, dialect));
+            }
+        }
+        for index in &schema.indexes {
+            if !old.indexes.contains(index) {
+                create_indexes.push(index.create_index_sql(table, dialect)?);
+            }
+        }
+    }
+    for schema in snapshot {
+        if find(declared, &schema.table).is_none() {
+            drop_tables.push(format!("DROP TABLE {}", schema.table));
+        }
+    }
+
+    Ok([
+        create_tables,
+        add_columns,
+        alter_columns,
+        alter_constraints_list,
+        drop_indexes,
+        drop_columns,
+        create_indexes,
+        drop_tables,
+    ]
+    .concat())
+}
+
+/// Read declared schema file, return error if file is missing
+pub fn read_schema(path: impl AsRef<Path>) -> io::Result<Vec<Schema>> {
+    let path = path.as_ref();
+    let data = std::fs::read_to_string(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))?;
+    serde_json::from_str(&data).map_err(io::Error::other)
+}
+
+/// Read snapshot file, missing file is empty snapshot of new project
+pub fn read_snapshot(path: impl AsRef<Path>) -> io::Result<Vec<Schema>> {
+    match read_schema(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        result => result,
+    }
+}
+
+pub fn write_snapshot(path: impl AsRef<Path>, schemas: &[Schema]) -> io::Result<()> {
+    let data = serde_json::to_string_pretty(schemas).map_err(io::Error::other)?;
+    std::fs::write(path, data + "\n")
+}
+
+/// Compare declared schemas (example: `vec![User::schema(), Post::schema()]`) with snapshot file
+pub fn generate_migration(
+    declared: Vec<Schema>,
+    snapshot_path: impl AsRef<Path>,
+    dialect: Dialect,
+) -> io::Result<Migration> {
+    let snapshot = read_snapshot(snapshot_path)?;
+    Ok(Migration {
+        statements: diff_schemas(&snapshot, &declared, dialect)?,
+        snapshot: declared,
+    })
+}
+
+/// Version of new migration: unix time, or next version after the latest migration of directory
+/// if it was written in the same second
+fn next_version(migrations_dir: &Path) -> io::Result<u64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(io::Error::other)?
+        .as_secs();
+    let latest = match std::fs::read_dir(migrations_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                let (version, _) = name.to_str()?.split_once('_')?;
+                version.parse::<u64>().ok()
+            })
+            .max(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    Ok(match latest {
+        Some(latest) if latest >= now => latest + 1,
+        _ => now,
+    })
+}
+
+/// Write `{migrations_dir}/{unix_time}_{description}.sql` and update snapshot file,
+/// version is unique within directory.
+///
+/// Return None if schema has no changes
+pub fn write_migration(
+    declared: Vec<Schema>,
+    snapshot_path: impl AsRef<Path>,
+    migrations_dir: impl AsRef<Path>,
+    description: &str,
+    dialect: Dialect,
+) -> io::Result<Option<PathBuf>> {
+    let snapshot_path = snapshot_path.as_ref();
+    let migration = generate_migration(declared, snapshot_path, dialect)?;
+    if migration.is_empty() {
+        return Ok(None);
+    }
+
+    let migrations_dir = migrations_dir.as_ref();
+    let version = next_version(migrations_dir)?;
+    std::fs::create_dir_all(migrations_dir)?;
+    let path = migrations_dir.join(format!("{version}_{description}.sql"));
+    std::fs::write(&path, migration.to_sql())?;
+    write_snapshot(snapshot_path, &migration.snapshot)?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Index, SqlType};
+
+    fn column(name: &str, sql_type: SqlType) -> Column {
+        Column {
+            name: name.to_string(),
+            sql_type,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            default: None,
+            check: None,
+        }
+    }
+
+    fn users(columns: Vec<Column>) -> Schema {
+        let id = Column {
+            primary_key: true,
+            ..column("id", SqlType::Integer)
+        };
+        Schema {
+            table: "users".to_string(),
+            columns: [vec![id], columns].concat(),
+            indexes: vec![],
+        }
+    }
+
+    #[test]
+    fn create_and_drop_tables() {
+        let declared = [users(vec![])];
+        let statements = diff_schemas(&[], &declared, Dialect::Postgres).unwrap();
+        assert_eq!(
+            statements,
+            ["CREATE TABLE IF NOT EXISTS users (\n    id INTEGER NOT NULL PRIMARY KEY\n)"]
+        );
+
+        let statements = diff_schemas(&declared, &[], Dialect::Postgres).unwrap();
+        assert_eq!(statements, ["DROP TABLE users"]);
+        assert!(
+            diff_schemas(&declared, &declared, Dialect::Postgres)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn add_alter_and_drop_columns() {
+        let snapshot = [users(vec![
+            column("name", SqlType::Text),
+            column("age", SqlType::Integer),
+        ])];
+        let declared = [users(vec![
+            Column {
+                nullable: true,
+                default: Some("'anonymous'".to_string()),
+                ..column("name", SqlType::Text)
+            },
+            Column {
+                default: Some("''".to_string()),
+                ..column("email", SqlType::Text)
+            },
+        ])];
+        let statements = diff_schemas(&snapshot, &declared, Dialect::Postgres).unwrap();
+        assert_eq!(
+            statements,
+            [
+                "ALTER TABLE users ADD COLUMN email TEXT NOT NULL DEFAULT ''",
+                "ALTER TABLE users ALTER COLUMN name DROP NOT NULL",
+                "ALTER TABLE users ALTER COLUMN name SET DEFAULT 'anonymous'",
+                "ALTER TABLE users DROP COLUMN age",
+            ]
+        );
+    }
+
+    #[test]
+    fn alter_constraints() {
+        let snapshot = [users(vec![
+            column("email", SqlType::Text),
+            Column {
+                check: Some("age > 0".to_string()),
+                ..column("age", SqlType::Integer)
+            },
+        ])];
+        let declared = [users(vec![
+            Column {
+                unique: true,
+                ..column("email", SqlType::Text)
+            },
+            Column {
+                check: Some("age >= 18".to_string()),
+                ..column("age", SqlType::Integer)
+            },
+        ])];
+        let statements = diff_schemas(&snapshot, &declared, Dialect::Postgres).unwrap();
+        assert_eq!(
+            statements,
+            [
+                "ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email)",
+                "ALTER TABLE users DROP CONSTRAINT users_age_check",
+                "ALTER TABLE users ADD CONSTRAINT users_age_check CHECK (age >= 18)",
+            ]
+        );
+
+        let statements = diff_schemas(&declared, &snapshot, Dialect::MySql);
+        assert_eq!(statements.unwrap_err().kind(), io::ErrorKind::Unsupported);
+        let statements = diff_schemas(&snapshot, &declared, Dialect::Sqlite);
+        assert_eq!(statements.unwrap_err().kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn alter_primary_key() {
+        let snapshot = [users(vec![])];
+        let mut declared = users(vec![Column {
+            primary_key: true,
+            default: Some("1".to_string()),
+            ..column("org_id", SqlType::Integer)
+        }]);
+        let statements = diff_schemas(&snapshot, &[declared.clone()], Dialect::Postgres).unwrap();
+        assert_eq!(
+            statements,
+            [
+                "ALTER TABLE users ADD COLUMN org_id INTEGER NOT NULL DEFAULT 1",
+                "ALTER TABLE users DROP CONSTRAINT users_pkey",
+                "ALTER TABLE users ADD PRIMARY KEY (id, org_id)",
+            ]
+        );
+
+        declared.columns[1].primary_key = false;
+        let statements = diff_schemas(&snapshot, &[declared], Dialect::MySql).unwrap();
+        assert_eq!(
+            statements,
+            ["ALTER TABLE users ADD COLUMN org_id INT NOT NULL DEFAULT 1"]
+        );
+    }
+
+    #[test]
+    fn add_not_null_column_requires_default() {
+        let snapshot = [users(vec![])];
+        let declared = [users(vec![column("email", SqlType::Text)])];
+        let statements = diff_schemas(&snapshot, &declared, Dialect::Postgres);
+        assert_eq!(statements.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+        let declared = [users(vec![Column {
+            nullable: true,
+            ..column("email", SqlType::Text)
+        }])];
+        let statements = diff_schemas(&snapshot, &declared, Dialect::Postgres).unwrap();
+        assert_eq!(statements, ["ALTER TABLE users ADD COLUMN email TEXT"]);
+    }
+
+    #[test]
+    fn sqlite_cant_alter_column() {
+        let snapshot = [users(vec![column("age", SqlType::Integer)])];
+        let declared = [users(vec![column("age", SqlType::BigInt)])];
+        let statements = diff_schemas(&snapshot, &declared, Dialect::Sqlite);
+        assert_eq!(statements.unwrap_err().kind(), io::ErrorKind::Unsupported);
+        assert!(diff_schemas(&snapshot, &declared, Dialect::MySql).is_ok());
+    }
+
+    #[test]
+    fn changed_index_is_recreated() {
+        let index = Index {
+            name: "idx_users_name".to_string(),
+            columns: vec!["name".to_string()],
+            unique: false,
+            predicate: None,
+        };
+        let mut snapshot = users(vec![column("name", SqlType::Text)]);
+        snapshot.indexes.push(index.clone());
+        let mut declared = snapshot.clone();
+        declared.indexes[0].unique = true;
+
+        let statements = diff_schemas(&[snapshot], &[declared], Dialect::Postgres).unwrap();
+        assert_eq!(
+            statements,
+            [
+                "DROP INDEX IF EXISTS idx_users_name",
+                "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_name ON users (name)",
+            ]
+        );
+    }
+
+    #[test]
+    fn mysql_cant_create_partial_index() {
+        let snapshot = users(vec![column("name", SqlType::Text)]);
+        let mut declared = snapshot.clone();
+        declared.indexes.push(Index {
+            name: "idx_users_name".to_string(),
+            columns: vec!["name".to_string()],
+            unique: false,
+            predicate: Some("name IS NOT NULL".to_string()),
+        });
+
+        let statements = diff_schemas(&[snapshot], &[declared], Dialect::MySql);
+        assert_eq!(statements.unwrap_err().kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn versions_of_migrations_are_unique() {
+        let dir = std::env::temp_dir().join(format!("sql_macros_versions_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let declared = |column_name: &str| {
+            vec![users(vec![Column {
+                nullable: true,
+                ..column(column_name, SqlType::Text)
+            }])]
+        };
+        let snapshot = dir.join("snapshot.json");
+        let paths = ["name", "email", "nick"]
+            .map(|name| write_migration(declared(name), &snapshot, &dir, name, Dialect::Postgres));
+        let mut versions = paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref().unwrap().as_ref().unwrap();
+                let name = path.file_name().unwrap().to_str().unwrap();
+                name.split_once('_').unwrap().0.parse::<u64>().unwrap()
+            })
+            .collect::<Vec<u64>>();
+        versions.dedup();
+        assert_eq!(versions.len(), 3);
+        assert!(versions.is_sorted());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_schema_file() {
+        let path = std::env::temp_dir().join("sql_macros_missing_schema.json");
+        assert_eq!(
+            read_schema(&path).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        assert!(read_snapshot(&path).unwrap().is_empty());
+    }
+}
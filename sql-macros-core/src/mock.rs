@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::{Crud, Repository};
+
+/// In-memory `Repository`, rows are stored in `HashMap` by primary key.
+///
+/// Use it in unit tests instead of `PgRepository`, `find_many_by` evaluates filters by `Crud::matches`
+#[derive(Debug)]
+pub struct MockRepository<T: Crud> {
+    rows: Mutex<HashMap<T::Id, T>>,
+}
+
+impl<T: Crud> Default for MockRepository<T> {
+    fn default() -> Self {
+        Self {
+            rows: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T> MockRepository<T>
+where
+    T: Crud + Clone,
+    T::Id: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create repository filled by rows
+    pub fn with_rows(rows: impl IntoIterator<Item = T>) -> Self {
+        let rows = rows.into_iter().map(|row| (row.id(), row)).collect();
+        Self {
+            rows: Mutex::new(rows),
+        }
+    }
+
+    /// Return copy of all stored rows
+    pub fn rows(&self) -> Vec<T> {
+        self.lock().values().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<T::Id, T>> {
+        self.rows.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Repository<T> for MockRepository<T>
+where
+    T: Crud + Clone,
+    T::Id: Eq + Hash,
+{
+    async fn find_by_id(&self, id: T::Id) -> Result<Option<T>, T::Error> {
+        Ok(self.lock().get(&id).cloned())
+    }
+
+    async fn find_all(&self) -> Result<Vec<T>, T::Error> {
+        Ok(self.rows())
+    }
+
+    async fn find_many_by(&self, filter: T::Eq) -> Result<Vec<T>, T::Error> {
+        let rows = self
+            .lock()
+            .values()
+            .filter(|row| row.matches(&filter))
+            .cloned()
+            .collect();
+        Ok(rows)
+    }
+
+    async fn insert(&self, row: &T) -> Result<T, T::Error> {
+        let mut rows = self.lock();
+        let id = row.id();
+        if rows.contains_key(&id) {
+            return Err(sqlx::Error::Protocol(format!(
+                "duplicate primary key in table {}",
+                T::name()
+            ))
+            .into());
+        }
+        rows.insert(id, row.clone());
+        Ok(row.clone())
+    }
+
+    async fn update(&self, row: &T) -> Result<T, T::Error> {
+        let mut rows = self.lock();
+        match rows.get_mut(&row.id()) {
+            Some(stored) => {
+                *stored = row.clone();
+                Ok(row.clone())
+            }
+            None => Err(sqlx::Error::RowNotFound.into()),
+        }
+    }
+
+    async fn delete(&self, id: T::Id) -> Result<u64, T::Error> {
+        Ok(self.lock().remove(&id).map_or(0, |_| 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use super::*;
+    use crate::{SqlTable, nullable_eq};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Member {
+        id: i32,
+        email: String,
+        team_id: Option<i32>,
+    }
+
+    enum MemberEq {
+        Email(String),
+        TeamId(Option<i32>),
+    }
+
+    impl SqlTable for Member {
+        fn name() -> &'static str {
+            "members"
+        }
+        fn fields() -> Vec<&'static str> {
+            vec!["id", "email", "team_id"]
+        }
+        fn sql_columns() -> Vec<&'static str> {
+            vec!["id", "email", "team_id"]
+        }
+    }
+
+    // Same as generated by `SqlTable`, queries are not used by mock
+    impl Crud for Member {
+        type Id = i32;
+        type Eq = MemberEq;
+        type Error = sqlx::Error;
+
+        fn id(&self) -> i32 {
+            self.id
+        }
+
+        async fn find_by_id(_: &sqlx::PgPool, _: i32) -> Result<Option<Self>, sqlx::Error> {
+            unreachable!()
+        }
+
+        async fn find_all(_: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
+            unreachable!()
+        }
+
+        async fn find_many_by(_: &sqlx::PgPool, _: MemberEq) -> Result<Vec<Self>, sqlx::Error> {
+            unreachable!()
+        }
+
+        fn matches(&self, filter: &MemberEq) -> bool {
+            match filter {
+                MemberEq::Email(email) => self.email == *email,
+                MemberEq::TeamId(team_id) => nullable_eq(self.team_id.as_ref(), team_id.as_ref()),
+            }
+        }
+
+        async fn insert(&self, _: &mut sqlx::PgConnection) -> Result<Self, sqlx::Error> {
+            unreachable!()
+        }
+
+        async fn update(&self, _: &mut sqlx::PgConnection) -> Result<Self, sqlx::Error> {
+            unreachable!()
+        }
+
+        async fn delete(_: &mut sqlx::PgConnection, _: i32) -> Result<u64, sqlx::Error> {
+            unreachable!()
+        }
+    }
+
+    /// Futures of mock are ready at first poll
+    fn ready<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("Future of mock is pending"),
+        }
+    }
+
+    fn member(id: i32, team_id: Option<i32>) -> Member {
+        Member {
+            id,
+            email: format!("{id}@x"),
+            team_id,
+        }
+    }
+
+    #[test]
+    fn null_filter_matches_nothing() {
+        let repo = MockRepository::with_rows([member(1, Some(7)), member(2, None)]);
+        let rows = ready(repo.find_many_by(MemberEq::TeamId(Some(7)))).unwrap();
+        assert_eq!(rows, vec![member(1, Some(7))]);
+        let rows = ready(repo.find_many_by(MemberEq::TeamId(None))).unwrap();
+        assert!(rows.is_empty());
+        let rows = ready(repo.find_many_by(MemberEq::Email("2@x".to_string()))).unwrap();
+        assert_eq!(rows, vec![member(2, None)]);
+    }
+
+    #[test]
+    fn insert_rejects_duplicate_key() {
+        let repo = MockRepository::new();
+        ready(repo.insert(&member(1, None))).unwrap();
+        let err = ready(repo.insert(&member(1, Some(7)))).unwrap_err();
+        assert!(matches!(err, sqlx::Error::Protocol(_)));
+        assert_eq!(repo.rows(), vec![member(1, None)]);
+    }
+
+    #[test]
+    fn update_and_delete_by_key() {
+        let repo = MockRepository::with_rows([member(1, None)]);
+        ready(repo.update(&member(1, Some(7)))).unwrap();
+        assert_eq!(ready(repo.find_by_id(1)).unwrap(), Some(member(1, Some(7))));
+        let err = ready(repo.update(&member(2, None))).unwrap_err();
+        assert!(matches!(err, sqlx::Error::RowNotFound));
+        assert_eq!(ready(repo.delete(1)).unwrap(), 1);
+        assert_eq!(ready(repo.delete(1)).unwrap(), 0);
+    }
+}
@@ -0,0 +1,413 @@
+use std::marker::PhantomData;
+
+use sqlx::postgres::PgRow;
+use sqlx::{Encode, FromRow, Postgres, QueryBuilder, Type};
+
+use crate::{OrderBy, SqlTable, push_order_by};
+
+type PushSql = Box<dyn FnOnce(&mut QueryBuilder<'static, Postgres>) + Send>;
+
+/// Column of table `T` with value `V`, generated by `#[derive(SqlTable)]` as `User::EMAIL`
+pub struct TypedColumn<T, V> {
+    name: &'static str,
+    column: &'static str,
+    _marker: PhantomData<fn() -> (T, V)>,
+}
+
+impl<T, V> Clone for TypedColumn<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for TypedColumn<T, V> {}
+
+impl<T, V> TypedColumn<T, V> {
+    pub const fn new(name: &'static str) -> Self {
+        Self::with_source(name, name)
+    }
+
+    /// Field `name` of view selected from qualified `column`, example: `("email", "users.email")`
+    pub const fn with_source(name: &'static str, column: &'static str) -> Self {
+        Self {
+            name,
+            column,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Name of field
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Column in sql, qualified for view
+    pub fn column(&self) -> &'static str {
+        self.column
+    }
+
+    pub fn asc(self) -> OrderBy {
+        OrderBy::asc(self.name)
+    }
+
+    pub fn desc(self) -> OrderBy {
+        OrderBy::desc(self.name)
+    }
+
+    pub fn is_null(self) -> Condition<T> {
+        let sql = format!("{} IS NULL", self.column);
+        Condition::new(move |builder| {
+            builder.push(sql);
+        })
+    }
+
+    pub fn is_not_null(self) -> Condition<T> {
+        let sql = format!("{} IS NOT NULL", self.column);
+        Condition::new(move |builder| {
+            builder.push(sql);
+        })
+    }
+}
+
+impl<T, V> TypedColumn<T, V>
+where
+    V: Encode<'static, Postgres> + Type<Postgres> + Send + 'static,
+{
+    fn compare(self, operator: &str, value: V) -> Condition<T> {
+        let sql = format!("{} {operator} ", self.column);
+        Condition::new(move |builder| {
+            builder.push(sql).push_bind(value);
+        })
+    }
+
+    pub fn eq(self, value: V) -> Condition<T> {
+        self.compare("=", value)
+    }
+
+    pub fn ne(self, value: V) -> Condition<T> {
+        self.compare("<>", value)
+    }
+
+    pub fn gt(self, value: V) -> Condition<T> {
+        self.compare(">", value)
+    }
+
+    pub fn gte(self, value: V) -> Condition<T> {
+        self.compare(">=", value)
+    }
+
+    pub fn lt(self, value: V) -> Condition<T> {
+        self.compare("<", value)
+    }
+
+    pub fn lte(self, value: V) -> Condition<T> {
+        self.compare("<=", value)
+    }
+}
+
+impl<T, V> TypedColumn<T, V>
+where
+    V: sqlx::postgres::PgHasArrayType + Encode<'static, Postgres> + Type<Postgres> + Send + 'static,
+{
+    /// `column = ANY($1)`
+    pub fn is_in(self, values: Vec<V>) -> Condition<T> {
+        let sql = format!("{} = ANY(", self.column);
+        Condition::new(move |builder| {
+            builder.push(sql).push_bind(values).push(")");
+        })
+    }
+}
+
+impl<T> TypedColumn<T, String> {
+    /// `column LIKE $1`, pattern is bound as is
+    pub fn like(self, pattern: impl Into<String>) -> Condition<T> {
+        self.compare("LIKE", pattern.into())
+    }
+}
+
+/// Condition of `WHERE` of table `T` with bound values
+pub struct Condition<T> {
+    push_sql: PushSql,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Condition<T> {
+    fn new(push_sql: impl FnOnce(&mut QueryBuilder<'static, Postgres>) + Send + 'static) -> Self {
+        Self {
+            push_sql: Box::new(push_sql),
+            _marker: PhantomData,
+        }
+    }
+
+    /// `(self OR other)`
+    pub fn or(self, other: Condition<T>) -> Condition<T> {
+        Condition::new(move |builder| {
+            builder.push("(");
+            (self.push_sql)(builder);
+            builder.push(" OR ");
+            (other.push_sql)(builder);
+            builder.push(")");
+        })
+    }
+
+    /// `(self AND other)`
+    pub fn and(self, other: Condition<T>) -> Condition<T> {
+        Condition::new(move |builder| {
+            builder.push("(");
+            (self.push_sql)(builder);
+            builder.push(" AND ");
+            (other.push_sql)(builder);
+            builder.push(")");
+        })
+    }
+}
+
+/// Query of table `T` built at runtime:
+/// `Query::<User>::select().filter(User::EMAIL.eq(email)).order_by(User::ID.desc()).limit(10)`
+pub struct Query<T> {
+    tenant: Option<Condition<T>>,
+    conditions: Vec<Condition<T>>,
+    order: Vec<OrderBy>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl<T: SqlTable> Query<T> {
+    pub fn select() -> Self {
+        Self {
+            tenant: None,
+            conditions: vec![],
+            order: vec![],
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /// Filter of tenant, it's required for table with `#[table(tenant = org_id)]`
+    pub fn tenant<V>(mut self, value: V) -> Self
+    where
+        V: Encode<'static, Postgres> + Type<Postgres> + Send + 'static,
+    {
+        self.tenant = Some(Condition::new(move |builder| {
+            let column = T::tenant().unwrap_or_default();
+            builder.push(format!("{column} = ")).push_bind(value);
+        }));
+        self
+    }
+
+    /// Conditions are joined by `AND`
+    pub fn filter(mut self, condition: Condition<T>) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn order_by(mut self, order_by: OrderBy) -> Self {
+        self.order.push(order_by);
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// `SELECT {columns} FROM {table} {joins} WHERE ... ORDER BY ... LIMIT $n OFFSET $n`.
+    ///
+    /// Return `sqlx::Error::ColumnNotFound` if column of order is not field of `T` and
+    /// `sqlx::Error::InvalidArgument` if tenant is missing or `T` has no tenant
+    pub fn build(self) -> Result<QueryBuilder<'static, Postgres>, sqlx::Error> {
+        let tenant = match (T::tenant(), self.tenant) {
+            (Some(_), None) => {
+                return Err(sqlx::Error::InvalidArgument(format!(
+                    "Query of {} must be filtered by tenant",
+                    T::name()
+                )));
+            }
+            (None, Some(_)) => {
+                return Err(sqlx::Error::InvalidArgument(format!(
+                    "{} has no tenant",
+                    T::name()
+                )));
+            }
+            (_, tenant) => tenant,
+        };
+        let mut builder = QueryBuilder::new(format!(
+            "SELECT {} FROM {}",
+            T::select_columns(),
+            T::from_clause()
+        ));
+        for (index, condition) in tenant.into_iter().chain(self.conditions).enumerate() {
+            builder.push(if index == 0 { " WHERE " } else { " AND " });
+            (condition.push_sql)(&mut builder);
+        }
+        push_order_by(&mut builder, &self.order, &T::columns())?;
+        if let Some(limit) = self.limit {
+            builder.push(" LIMIT ").push_bind(limit);
+        }
+        if let Some(offset) = self.offset {
+            builder.push(" OFFSET ").push_bind(offset);
+        }
+        Ok(builder)
+    }
+}
+
+impl<T> Query<T>
+where
+    T: SqlTable + for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    /// Executor is pool, connection or `&mut **tx` of `RlsTransaction` for `#[table(rls)]`
+    pub async fn fetch_all<'e, E>(self, executor: E) -> Result<Vec<T>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let mut builder = self.build()?;
+        builder.build_query_as::<T>().fetch_all(executor).await
+    }
+
+    pub async fn fetch_optional<'e, E>(self, executor: E) -> Result<Option<T>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let mut builder = self.build()?;
+        builder.build_query_as::<T>().fetch_optional(executor).await
+    }
+
+    pub async fn fetch_one<'e, E>(self, executor: E) -> Result<T, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let mut builder = self.build()?;
+        builder.build_query_as::<T>().fetch_one(executor).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct User;
+
+    impl User {
+        const ID: TypedColumn<User, i32> = TypedColumn::new("id");
+        const EMAIL: TypedColumn<User, String> = TypedColumn::new("email");
+    }
+
+    impl SqlTable for User {
+        fn name() -> &'static str {
+            "users"
+        }
+        fn fields() -> Vec<&'static str> {
+            vec!["id", "email"]
+        }
+        fn sql_columns() -> Vec<&'static str> {
+            vec!["id", "email"]
+        }
+    }
+
+    #[test]
+    fn build_select() {
+        let builder = Query::<User>::select()
+            .filter(User::EMAIL.eq("a@b.c".to_string()).or(User::ID.is_null()))
+            .filter(User::ID.gt(10))
+            .order_by(User::ID.desc())
+            .order_by(User::EMAIL.asc())
+            .limit(10)
+            .offset(20)
+            .build()
+            .unwrap();
+        assert_eq!(
+            builder.sql(),
+            "SELECT id, email FROM users WHERE (email = $1 OR id IS NULL) AND id > $2 ORDER BY id DESC, email ASC LIMIT $3 OFFSET $4"
+        );
+    }
+
+    struct PostView;
+
+    impl PostView {
+        const EMAIL: TypedColumn<PostView, String> =
+            TypedColumn::with_source("author_email", "users.email");
+    }
+
+    impl SqlTable for PostView {
+        fn name() -> &'static str {
+            "posts"
+        }
+        fn from_clause() -> &'static str {
+            "posts JOIN users ON users.id = posts.user_id"
+        }
+        fn fields() -> Vec<&'static str> {
+            vec!["author_email"]
+        }
+        fn sql_columns() -> Vec<&'static str> {
+            vec!["users.email AS author_email"]
+        }
+        fn columns() -> Vec<(&'static str, &'static str)> {
+            vec![("author_email", "users.email")]
+        }
+    }
+
+    #[test]
+    fn build_select_of_view() {
+        let builder = Query::<PostView>::select()
+            .filter(PostView::EMAIL.like("%@b.c"))
+            .order_by(PostView::EMAIL.asc())
+            .build()
+            .unwrap();
+        assert_eq!(
+            builder.sql(),
+            "SELECT users.email AS author_email FROM posts JOIN users ON users.id = posts.user_id WHERE users.email LIKE $1 ORDER BY users.email ASC"
+        );
+    }
+
+    struct Project;
+
+    impl Project {
+        const ID: TypedColumn<Project, i32> = TypedColumn::new("id");
+    }
+
+    impl SqlTable for Project {
+        fn name() -> &'static str {
+            "projects"
+        }
+        fn fields() -> Vec<&'static str> {
+            vec!["id", "org_id"]
+        }
+        fn sql_columns() -> Vec<&'static str> {
+            vec!["id", "org_id"]
+        }
+        fn tenant() -> Option<&'static str> {
+            Some("org_id")
+        }
+    }
+
+    #[test]
+    fn build_select_of_tenant() {
+        let builder = Query::<Project>::select()
+            .filter(Project::ID.eq(1))
+            .tenant(7)
+            .build()
+            .unwrap();
+        assert_eq!(
+            builder.sql(),
+            "SELECT id, org_id FROM projects WHERE org_id = $1 AND id = $2"
+        );
+
+        let result = Query::<Project>::select().build();
+        assert!(matches!(result, Err(sqlx::Error::InvalidArgument(_))));
+        let result = Query::<User>::select().tenant(7).build();
+        assert!(matches!(result, Err(sqlx::Error::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn order_by_unknown_column() {
+        let result = Query::<User>::select()
+            .order_by(OrderBy::asc("id; DROP TABLE users"))
+            .build();
+        assert!(matches!(result, Err(sqlx::Error::ColumnNotFound(_))));
+    }
+}
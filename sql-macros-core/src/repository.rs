@@ -0,0 +1,116 @@
+use std::future::Future;
+
+use crate::SqlTable;
+
+/// CRUD queries of a table with a primary key.
+///
+/// Implemented by `#[derive(SqlCrud)]` for fields marked `#[table(primary_key)]`
+pub trait Crud: SqlTable + Sized + Send + Sync + Unpin {
+    /// Type of the primary key, a tuple for composite keys
+    type Id: Send + Sync;
+    /// Equality filters generated from `select` and `select_many` attributes
+    type Eq: Send + Sync;
+    /// Error of `#[table(error = sql_macros::Error)]`, default `sqlx::Error`
+    type Error: From<sqlx::Error> + Send;
+
+    fn id(&self) -> Self::Id;
+
+    fn find_by_id(
+        pool: &sqlx::PgPool,
+        id: Self::Id,
+    ) -> impl Future<Output = Result<Option<Self>, Self::Error>> + Send;
+
+    fn find_all(pool: &sqlx::PgPool)
+    -> impl Future<Output = Result<Vec<Self>, Self::Error>> + Send;
+
+    fn find_many_by(
+        pool: &sqlx::PgPool,
+        filter: Self::Eq,
+    ) -> impl Future<Output = Result<Vec<Self>, Self::Error>> + Send;
+
+    /// Check the row by filter without database, like `WHERE` of `find_many_by`
+    fn matches(&self, filter: &Self::Eq) -> bool;
+
+    fn insert(
+        &self,
+        conn: &mut sqlx::PgConnection,
+    ) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+
+    fn update(
+        &self,
+        conn: &mut sqlx::PgConnection,
+    ) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+
+    /// Return count of deleted rows
+    fn delete(
+        conn: &mut sqlx::PgConnection,
+        id: Self::Id,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+}
+
+/// Comparison of `Crud::matches` for nullable column, `NULL = NULL` is not true in SQL, so `None`
+/// doesn't match anything, like `WHERE team_id=$1` of `find_many_by`
+pub fn nullable_eq<T: PartialEq>(value: Option<&T>, filter: Option<&T>) -> bool {
+    matches!((value, filter), (Some(value), Some(filter)) if value == filter)
+}
+
+/// Storage of `T` rows, take `impl Repository<User>` to be able to swap implementations
+pub trait Repository<T: Crud>: Send + Sync {
+    fn find_by_id(&self, id: T::Id) -> impl Future<Output = Result<Option<T>, T::Error>> + Send;
+
+    fn find_all(&self) -> impl Future<Output = Result<Vec<T>, T::Error>> + Send;
+
+    fn find_many_by(&self, filter: T::Eq) -> impl Future<Output = Result<Vec<T>, T::Error>> + Send;
+
+    fn insert(&self, row: &T) -> impl Future<Output = Result<T, T::Error>> + Send;
+
+    fn update(&self, row: &T) -> impl Future<Output = Result<T, T::Error>> + Send;
+
+    /// Return count of deleted rows
+    fn delete(&self, id: T::Id) -> impl Future<Output = Result<u64, T::Error>> + Send;
+}
+
+/// `Repository` over a Postgres pool, it just calls `Crud` methods
+#[derive(Debug, Clone)]
+pub struct PgRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PgRepository {
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &sqlx::PgPool {
+        &self.pool
+    }
+}
+
+impl<T: Crud> Repository<T> for PgRepository {
+    async fn find_by_id(&self, id: T::Id) -> Result<Option<T>, T::Error> {
+        T::find_by_id(&self.pool, id).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<T>, T::Error> {
+        T::find_all(&self.pool).await
+    }
+
+    async fn find_many_by(&self, filter: T::Eq) -> Result<Vec<T>, T::Error> {
+        T::find_many_by(&self.pool, filter).await
+    }
+
+    async fn insert(&self, row: &T) -> Result<T, T::Error> {
+        let mut conn = self.pool.acquire().await?;
+        row.insert(&mut conn).await
+    }
+
+    async fn update(&self, row: &T) -> Result<T, T::Error> {
+        let mut conn = self.pool.acquire().await?;
+        row.update(&mut conn).await
+    }
+
+    async fn delete(&self, id: T::Id) -> Result<u64, T::Error> {
+        let mut conn = self.pool.acquire().await?;
+        T::delete(&mut conn, id).await
+    }
+}
@@ -0,0 +1,95 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::PgTransaction;
+
+/// Session variable of current user for policies of row level security:
+/// `USING (owner_id = current_setting('app.current_user')::int)`
+pub const CURRENT_USER: &str = "app.current_user";
+
+/// Set session variable until end of transaction, same as `SET LOCAL key = value`.
+///
+/// Outside of transaction value is reset after this query, use `RlsTransaction` instead.
+pub async fn set_local(
+    conn: &mut sqlx::PgConnection,
+    key: &str,
+    value: &str,
+) -> Result<(), sqlx::Error> {
+    // SET LOCAL doesn't accept params, set_config(.., true) is the same
+    sqlx::query("SELECT set_config($1, $2, true)")
+        .bind(key)
+        .bind(value)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Transaction with session variables of row level security, generated methods of struct
+/// with `#[table(rls)]` take it instead of pool and connection.
+///
+/// ```ignore
+/// let mut tx = RlsTransaction::begin(&pool, &user_id.to_string()).await?;
+/// let posts = Post::select_many_by_title(&mut tx, title).await?;
+/// tx.commit().await?;
+/// ```
+pub struct RlsTransaction {
+    tx: PgTransaction,
+}
+
+impl RlsTransaction {
+    /// Begin transaction and set `app.current_user`
+    pub async fn begin(pool: &sqlx::PgPool, user: &str) -> Result<Self, sqlx::Error> {
+        Self::begin_with(pool, &[(CURRENT_USER, user)]).await
+    }
+
+    /// Begin transaction and set variables, example: `[("app.tenant", "7")]`
+    pub async fn begin_with(
+        pool: &sqlx::PgPool,
+        settings: &[(&str, &str)],
+    ) -> Result<Self, sqlx::Error> {
+        Self::scope(pool.begin().await?, settings).await
+    }
+
+    /// Set variables in started transaction.
+    ///
+    /// Return `sqlx::Error::InvalidArgument` for empty settings, transaction is rolled back
+    pub async fn scope(tx: PgTransaction, settings: &[(&str, &str)]) -> Result<Self, sqlx::Error> {
+        if settings.is_empty() {
+            return Err(sqlx::Error::InvalidArgument(
+                "RlsTransaction must set at least one variable".to_string(),
+            ));
+        }
+        let mut tx = Self { tx };
+        for (key, value) in settings {
+            tx.set(key, value).await?;
+        }
+        Ok(tx)
+    }
+
+    /// Set one more variable until end of transaction
+    pub async fn set(&mut self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        set_local(&mut self.tx, key, value).await
+    }
+
+    pub async fn commit(self) -> Result<(), sqlx::Error> {
+        self.tx.commit().await
+    }
+
+    pub async fn rollback(self) -> Result<(), sqlx::Error> {
+        self.tx.rollback().await
+    }
+}
+
+/// Methods of structs without `#[table(rls)]` take it as `&mut sqlx::PgConnection`
+impl Deref for RlsTransaction {
+    type Target = sqlx::PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl DerefMut for RlsTransaction {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tx
+    }
+}
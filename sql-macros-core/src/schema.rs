@@ -0,0 +1,393 @@
+use std::io;
+
+/// SQL dialect for generated DDL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+/// Column type mapped from rust type of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "migrate", derive(serde::Serialize, serde::Deserialize))]
+pub enum SqlType {
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    Double,
+    Numeric,
+    Boolean,
+    Text,
+    Bytes,
+    Uuid,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Json,
+    Array(Box<SqlType>),
+    /// Type created in database, for example enum `role` for `sqlx::Type` enum `Role`
+    Custom(String),
+}
+
+impl SqlType {
+    pub fn to_sql(&self, dialect: Dialect) -> String {
+        let sql_type = match dialect {
+            Dialect::Postgres => match self {
+                SqlType::SmallInt => "SMALLINT",
+                SqlType::Integer => "INTEGER",
+                SqlType::BigInt => "BIGINT",
+                SqlType::Real => "REAL",
+                SqlType::Double => "DOUBLE PRECISION",
+                SqlType::Numeric => "NUMERIC",
+                SqlType::Boolean => "BOOLEAN",
+                SqlType::Text => "TEXT",
+                SqlType::Bytes => "BYTEA",
+                SqlType::Uuid => "UUID",
+                SqlType::Date => "DATE",
+                SqlType::Time => "TIME",
+                SqlType::Timestamp => "TIMESTAMP",
+                SqlType::TimestampTz => "TIMESTAMPTZ",
+                SqlType::Json => "JSONB",
+                SqlType::Array(inner) => return format!("{}[]", inner.to_sql(dialect)),
+                SqlType::Custom(name) => return name.clone(),
+            },
+            Dialect::MySql => match self {
+                SqlType::SmallInt => "SMALLINT",
+                SqlType::Integer => "INT",
+                SqlType::BigInt => "BIGINT",
+                SqlType::Real => "FLOAT",
+                SqlType::Double => "DOUBLE",
+                SqlType::Numeric => "DECIMAL",
+                SqlType::Boolean => "BOOLEAN",
+                SqlType::Text => "TEXT",
+                SqlType::Bytes => "BLOB",
+                SqlType::Uuid => "CHAR(36)",
+                SqlType::Date => "DATE",
+                SqlType::Time => "TIME",
+                SqlType::Timestamp => "DATETIME",
+                SqlType::TimestampTz => "TIMESTAMP",
+                SqlType::Json | SqlType::Array(_) => "JSON",
+                // MySQL has no named types, enums are stored as text
+                SqlType::Custom(_) => "TEXT",
+            },
+            Dialect::Sqlite => match self {
+                SqlType::SmallInt | SqlType::Integer | SqlType::BigInt | SqlType::Boolean => {
+                    "INTEGER"
+                }
+                SqlType::Real | SqlType::Double => "REAL",
+                SqlType::Numeric => "NUMERIC",
+                SqlType::Bytes => "BLOB",
+                SqlType::Text
+                | SqlType::Uuid
+                | SqlType::Date
+                | SqlType::Time
+                | SqlType::Timestamp
+                | SqlType::TimestampTz
+                | SqlType::Json
+                | SqlType::Array(_)
+                | SqlType::Custom(_) => "TEXT",
+            },
+        };
+        sql_type.to_string()
+    }
+}
+
+/// Column of a table, built by `#[derive(SqlSchema)]` from a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "migrate", derive(serde::Serialize, serde::Deserialize))]
+pub struct Column {
+    pub name: String,
+    pub sql_type: SqlType,
+    pub nullable: bool,
+    pub primary_key: bool,
+    pub unique: bool,
+    pub default: Option<String>,
+    pub check: Option<String>,
+}
+
+impl Column {
+    /// Column definition for `CREATE TABLE` or `ADD COLUMN`, example: `email TEXT NOT NULL UNIQUE`
+    pub fn to_sql(&self, dialect: Dialect) -> String {
+        self.definition(dialect, true)
+    }
+
+    pub(crate) fn definition(&self, dialect: Dialect, inline_primary_key: bool) -> String {
+        let indexed = self.primary_key || self.unique;
+        let sql_type = match (&self.sql_type, dialect) {
+            // MySQL can't index TEXT without prefix length
+            (SqlType::Text, Dialect::MySql) if indexed => "VARCHAR(255)".to_string(),
+            (sql_type, dialect) => sql_type.to_sql(dialect),
+        };
+
+        let mut definition = format!("{} {sql_type}", self.name);
+        if !self.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if self.primary_key && inline_primary_key {
+            definition.push_str(" PRIMARY KEY");
+        }
+        if self.unique {
+            definition.push_str(" UNIQUE");
+        }
+        if let Some(default) = &self.default {
+            definition.push_str(&format!(" DEFAULT {default}"));
+        }
+        if let Some(check) = &self.check {
+            definition.push_str(&format!(" CHECK ({check})"));
+        }
+        definition
+    }
+}
+
+/// Index of a table, `#[table(index)]` of field or `#[table(index(name = idx_x, columns = (a, b)))]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "migrate", derive(serde::Serialize, serde::Deserialize))]
+pub struct Index {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    /// Condition of partial index, example: `deleted_at IS NULL`
+    pub predicate: Option<String>,
+}
+
+impl Index {
+    /// Example: `CREATE UNIQUE INDEX IF NOT EXISTS idx_x ON users (a, b) WHERE deleted_at IS NULL`
+    ///
+    /// Return error of kind `Unsupported` for partial index of MySQL
+    pub fn create_index_sql(&self, table: &str, dialect: Dialect) -> io::Result<String> {
+        let unique = if self.unique { "UNIQUE " } else { "" };
+        let if_not_exists = match dialect {
+            Dialect::MySql => "",
+            Dialect::Postgres | Dialect::Sqlite => "IF NOT EXISTS ",
+        };
+        let mut sql = format!(
+            "CREATE {unique}INDEX {if_not_exists}{} ON {table} ({})",
+            self.name,
+            self.columns.join(", ")
+        );
+        if let Some(predicate) = &self.predicate {
+            if dialect == Dialect::MySql {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("MySQL has no partial indexes, can't create {}", self.name),
+                ));
+            }
+            sql.push_str(&format!(" WHERE {predicate}"));
+        }
+        Ok(sql)
+    }
+
+    pub fn drop_index_sql(&self, table: &str, dialect: Dialect) -> String {
+        match dialect {
+            Dialect::MySql => format!("DROP INDEX {} ON {table}", self.name),
+            Dialect::Postgres | Dialect::Sqlite => format!("DROP INDEX IF EXISTS {}", self.name),
+        }
+    }
+}
+
+/// Declared schema of a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "migrate", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schema {
+    pub table: String,
+    pub columns: Vec<Column>,
+    #[cfg_attr(feature = "migrate", serde(default))]
+    pub indexes: Vec<Index>,
+}
+
+impl Schema {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    pub fn primary_key(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .filter(|column| column.primary_key)
+            .map(|column| column.name.as_str())
+            .collect()
+    }
+
+    /// Example: `CREATE TABLE IF NOT EXISTS users (id INTEGER NOT NULL PRIMARY KEY, email TEXT NOT NULL)`
+    pub fn create_table_sql(&self, dialect: Dialect) -> String {
+        let primary_key = self.primary_key();
+        let inline_primary_key = primary_key.len() == 1;
+
+        let mut definitions = self
+            .columns
+            .iter()
+            .map(|column| format!("    {}", column.definition(dialect, inline_primary_key)))
+            .collect::<Vec<String>>();
+        if !inline_primary_key && !primary_key.is_empty() {
+            definitions.push(format!("    PRIMARY KEY ({})", primary_key.join(", ")));
+        }
+
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} (\n{}\n)",
+            self.table,
+            definitions.join(",\n")
+        )
+    }
+
+    /// `CREATE TABLE` followed by `CREATE INDEX` statements
+    pub fn ddl(&self, dialect: Dialect) -> io::Result<Vec<String>> {
+        let mut statements = vec![self.create_table_sql(dialect)];
+        for index in &self.indexes {
+            statements.push(index.create_index_sql(&self.table, dialect)?);
+        }
+        Ok(statements)
+    }
+}
+
+/// Implemented by `#[derive(SqlSchema)]`
+pub trait SqlSchema {
+    fn schema() -> Schema;
+
+    fn create_table_sql(dialect: Dialect) -> String {
+        Self::schema().create_table_sql(dialect)
+    }
+
+    /// Statements to create table with indexes
+    fn ddl(dialect: Dialect) -> io::Result<Vec<String>> {
+        Self::schema().ddl(dialect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, sql_type: SqlType) -> Column {
+        Column {
+            name: name.to_string(),
+            sql_type,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            default: None,
+            check: None,
+        }
+    }
+
+    fn users() -> Schema {
+        Schema {
+            table: "users".to_string(),
+            columns: vec![
+                Column {
+                    primary_key: true,
+                    ..column("id", SqlType::Integer)
+                },
+                Column {
+                    unique: true,
+                    ..column("email", SqlType::Text)
+                },
+                Column {
+                    nullable: true,
+                    ..column("role", SqlType::Custom("role".to_string()))
+                },
+                Column {
+                    default: Some("0".to_string()),
+                    check: Some("balance >= 0".to_string()),
+                    ..column("balance", SqlType::BigInt)
+                },
+                column("tags", SqlType::Array(Box::new(SqlType::Text))),
+            ],
+            indexes: vec![Index {
+                name: "idx_users_role".to_string(),
+                columns: vec!["role".to_string(), "email".to_string()],
+                unique: true,
+                predicate: Some("role IS NOT NULL".to_string()),
+            }],
+        }
+    }
+
+    #[test]
+    fn create_table_of_dialects() {
+        assert_eq!(
+            users().create_table_sql(Dialect::Postgres),
+            "CREATE TABLE IF NOT EXISTS users (
+    id INTEGER NOT NULL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    role role,
+    balance BIGINT NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    tags TEXT[] NOT NULL
+)"
+        );
+        assert_eq!(
+            users().create_table_sql(Dialect::MySql),
+            "CREATE TABLE IF NOT EXISTS users (
+    id INT NOT NULL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL UNIQUE,
+    role TEXT,
+    balance BIGINT NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    tags JSON NOT NULL
+)"
+        );
+        assert_eq!(
+            users().create_table_sql(Dialect::Sqlite),
+            "CREATE TABLE IF NOT EXISTS users (
+    id INTEGER NOT NULL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    role TEXT,
+    balance INTEGER NOT NULL DEFAULT 0 CHECK (balance >= 0),
+    tags TEXT NOT NULL
+)"
+        );
+    }
+
+    #[test]
+    fn composite_primary_key() {
+        let schema = Schema {
+            table: "members".to_string(),
+            columns: vec![
+                Column {
+                    primary_key: true,
+                    ..column("org_id", SqlType::Integer)
+                },
+                Column {
+                    primary_key: true,
+                    ..column("user_id", SqlType::Uuid)
+                },
+            ],
+            indexes: vec![],
+        };
+        assert_eq!(
+            schema.create_table_sql(Dialect::Postgres),
+            "CREATE TABLE IF NOT EXISTS members (
+    org_id INTEGER NOT NULL,
+    user_id UUID NOT NULL,
+    PRIMARY KEY (org_id, user_id)
+)"
+        );
+        assert_eq!(schema.primary_key(), ["org_id", "user_id"]);
+    }
+
+    #[test]
+    fn ddl_with_indexes() {
+        let schema = users();
+        assert_eq!(
+            schema.ddl(Dialect::Postgres).unwrap()[1],
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_role ON users (role, email) WHERE role IS NOT NULL"
+        );
+        // MySQL has no partial indexes
+        assert_eq!(
+            schema.ddl(Dialect::MySql).unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+        let index = Index {
+            predicate: None,
+            ..schema.indexes[0].clone()
+        };
+        assert_eq!(
+            index.create_index_sql("users", Dialect::MySql).unwrap(),
+            "CREATE UNIQUE INDEX idx_users_role ON users (role, email)"
+        );
+        assert_eq!(
+            schema.indexes[0].drop_index_sql("users", Dialect::MySql),
+            "DROP INDEX idx_users_role ON users"
+        );
+    }
+}
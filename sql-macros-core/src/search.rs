@@ -0,0 +1,32 @@
+/// Query of FTS5 `MATCH` for searches of `#[table(dialect = sqlite)]`, example:
+/// `{title body} : ("rust" "e-mail")`.
+/// Every term is quoted as string of FTS5, so operators and characters like `-`, `@` or `"`
+/// of user input are matched as text, None if input has no terms
+pub fn fts5_query(columns: &str, input: &str) -> Option<String> {
+    let terms = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<String>>();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{columns} : ({})", terms.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_terms_of_fts5_query() {
+        assert_eq!(
+            fts5_query("{title body}", r#"say "hi" e-mail a@x.com"#).as_deref(),
+            Some(r#"{title body} : ("say" """hi""" "e-mail" "a@x.com")"#)
+        );
+        assert_eq!(
+            fts5_query("{title}", "rust OR NOT sql*").as_deref(),
+            Some(r#"{title} : ("rust" "OR" "NOT" "sql*")"#)
+        );
+        assert_eq!(fts5_query("{title}", "  "), None);
+    }
+}
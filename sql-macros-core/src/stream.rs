@@ -0,0 +1,67 @@
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+/// Stream of `stream_*` methods which converts `sqlx::Error` into error of `#[table(error)]`
+pub struct ErrInto<S, E> {
+    stream: S,
+    error: PhantomData<fn() -> E>,
+}
+
+/// Convert errors of rows stream, example: `err_into::<_, sql_macros::Error>(query.fetch(conn))`
+pub fn err_into<S, E>(stream: S) -> ErrInto<S, E> {
+    ErrInto {
+        stream,
+        error: PhantomData,
+    }
+}
+
+impl<S, T, E> Stream for ErrInto<S, E>
+where
+    S: Stream<Item = Result<T, sqlx::Error>> + Unpin,
+    E: From<sqlx::Error>,
+{
+    type Item = Result<T, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().stream)
+            .poll_next(cx)
+            .map(|item| item.map(|row| row.map_err(E::from)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rows(Vec<Result<i32, sqlx::Error>>);
+
+    impl Stream for Rows {
+        type Item = Result<i32, sqlx::Error>;
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let rows = &mut self.get_mut().0;
+            Poll::Ready((!rows.is_empty()).then(|| rows.remove(0)))
+        }
+    }
+
+    #[test]
+    fn convert_errors_of_stream() {
+        let mut stream =
+            err_into::<_, crate::Error>(Rows(vec![Ok(1), Err(sqlx::Error::RowNotFound)]));
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        let mut next = || match Pin::new(&mut stream).poll_next(&mut cx) {
+            Poll::Ready(item) => item,
+            Poll::Pending => panic!("Rows are ready"),
+        };
+        assert!(matches!(next(), Some(Ok(1))));
+        assert!(matches!(next(), Some(Err(crate::Error::NotFound))));
+        assert!(next().is_none());
+    }
+}
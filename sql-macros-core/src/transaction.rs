@@ -0,0 +1,167 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+/// Transaction passed to closure of `with_transaction`, generated write methods take it as
+/// `&mut sqlx::PgConnection` by deref: `user.insert(tx).await`
+pub type PgTransaction = sqlx::Transaction<'static, sqlx::Postgres>;
+
+/// How many times transaction is run when it fails by serialization failure (SQLSTATE 40001)
+/// or deadlock (SQLSTATE 40P01), and how long to wait before next attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including first one, 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before second attempt, it's doubled for each next one, zero retries immediately
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            backoff: Duration::from_millis(10),
+        }
+    }
+
+    pub fn no_retry() -> Self {
+        Self::new(1)
+    }
+
+    pub fn with_backoff(self, backoff: Duration) -> Self {
+        Self { backoff, ..self }
+    }
+
+    /// Delay before attempt, example: 10ms, 20ms, 40ms for attempts 2, 3, 4
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(2)))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Error of transaction closure, it tells when transaction can be run again
+pub trait TransactionError: From<sqlx::Error> {
+    fn is_serialization_failure(&self) -> bool;
+}
+
+impl TransactionError for sqlx::Error {
+    fn is_serialization_failure(&self) -> bool {
+        matches!(
+            self,
+            sqlx::Error::Database(err) if matches!(err.code().as_deref(), Some("40001" | "40P01"))
+        )
+    }
+}
+
+/// Run closure in transaction: commit on `Ok`, rollback on `Err` and run again on serialization failure
+pub trait Transactional {
+    fn transaction_with<T, E, F>(
+        &self,
+        policy: RetryPolicy,
+        f: F,
+    ) -> impl Future<Output = Result<T, E>> + Send
+    where
+        T: Send,
+        E: TransactionError + Send,
+        F: for<'c> FnMut(&'c mut PgTransaction) -> BoxFuture<'c, Result<T, E>> + Send;
+
+    fn transaction<T, E, F>(&self, f: F) -> impl Future<Output = Result<T, E>> + Send
+    where
+        T: Send,
+        E: TransactionError + Send,
+        F: for<'c> FnMut(&'c mut PgTransaction) -> BoxFuture<'c, Result<T, E>> + Send,
+    {
+        self.transaction_with(RetryPolicy::default(), f)
+    }
+}
+
+impl Transactional for sqlx::PgPool {
+    async fn transaction_with<T, E, F>(&self, policy: RetryPolicy, mut f: F) -> Result<T, E>
+    where
+        T: Send,
+        E: TransactionError + Send,
+        F: for<'c> FnMut(&'c mut PgTransaction) -> BoxFuture<'c, Result<T, E>> + Send,
+    {
+        let mut attempt = 1;
+        loop {
+            let mut tx = self.begin().await?;
+            let result = match f(&mut tx).await {
+                Ok(value) => tx.commit().await.map(|_| value).map_err(E::from),
+                Err(err) => {
+                    // error of closure is more useful than error of rollback
+                    let _ = tx.rollback().await;
+                    Err(err)
+                }
+            };
+            match result {
+                Err(err) if err.is_serialization_failure() && attempt < policy.max_attempts => {
+                    attempt += 1;
+                    let delay = policy.delay(attempt);
+                    if !delay.is_zero() {
+                        sqlx_core::rt::sleep(delay).await;
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Run closure in transaction with default `RetryPolicy`, closure is called for each attempt,
+/// so owned values are cloned before `async move`
+///
+/// ```no_run
+/// use sql_macros_core::with_transaction;
+///
+/// async fn register(pool: &sqlx::PgPool, email: String) -> Result<i32, sqlx::Error> {
+///     with_transaction(pool, |tx| {
+///         let email = email.clone();
+///         Box::pin(async move {
+///             let (id,): (i32,) = sqlx::query_as("INSERT INTO users (email) VALUES ($1) RETURNING id")
+///                 .bind(email)
+///                 .fetch_one(&mut **tx)
+///                 .await?;
+///             sqlx::query("INSERT INTO profiles (user_id) VALUES ($1)")
+///                 .bind(id)
+///                 .execute(&mut **tx)
+///                 .await?;
+///             Ok(id)
+///         })
+///     })
+///     .await
+/// }
+/// ```
+pub async fn with_transaction<T, E, F>(pool: &sqlx::PgPool, f: F) -> Result<T, E>
+where
+    T: Send,
+    E: TransactionError + Send,
+    F: for<'c> FnMut(&'c mut PgTransaction) -> BoxFuture<'c, Result<T, E>> + Send,
+{
+    pool.transaction(f).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_is_doubled() {
+        let policy = RetryPolicy::new(4);
+        assert_eq!(policy.delay(2), Duration::from_millis(10));
+        assert_eq!(policy.delay(3), Duration::from_millis(20));
+        assert_eq!(policy.delay(4), Duration::from_millis(40));
+    }
+
+    #[test]
+    fn zero_backoff_retries_immediately() {
+        let policy = RetryPolicy::default().with_backoff(Duration::ZERO);
+        assert_eq!(policy.delay(3), Duration::ZERO);
+    }
+}
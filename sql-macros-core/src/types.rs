@@ -2,4 +2,46 @@ pub trait SqlTable {
     fn name() -> &'static str;
     fn fields() -> Vec<&'static str>;
     fn sql_columns() -> Vec<&'static str>;
+
+    /// Table with joins of view `#[table(from = orders, join(..))]`
+    fn from_clause() -> &'static str {
+        Self::name()
+    }
+
+    /// Column of tenant `#[table(tenant = org_id)]`, queries of such table must be filtered by it
+    fn tenant() -> Option<&'static str> {
+        None
+    }
+
+    /// Pairs of field and sql column, used to check `OrderBy` of runtime queries
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        Self::fields()
+            .into_iter()
+            .map(|field| (field, field))
+            .collect()
+    }
+
+    /// Columns for queries built at runtime, `SqlTable` derive generates them without type
+    /// overrides of `query_as!`: `role AS "role!: Role"` -> `role`
+    fn select_columns() -> String {
+        Self::sql_columns().join(", ")
+    }
+}
+
+/// Foreign key of related row which is grouped by `load_*_for` of `SqlRelations`, nullable key
+/// `Option<K>` is skipped when it's `None`
+pub trait ForeignKey<K> {
+    fn key(self) -> Option<K>;
+}
+
+impl<K> ForeignKey<K> for K {
+    fn key(self) -> Option<K> {
+        Some(self)
+    }
+}
+
+impl<K> ForeignKey<K> for Option<K> {
+    fn key(self) -> Option<K> {
+        self
+    }
 }
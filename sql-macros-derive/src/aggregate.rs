@@ -0,0 +1,254 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use quote::{format_ident, quote};
+
+use crate::parser::{
+    Table, extract_fields_as_params, fields_named_struct, generate_sql_params_condition,
+    get_bind_params, get_filters, get_method_params, group_idents, option_inner, scope_tenant,
+    to_camel_case,
+};
+use crate::schema::{ColumnType, column_type};
+
+/// Column of result struct: sql expression, name and rust type
+struct AggregateColumn {
+    sql: String,
+    name: String,
+    ty: proc_macro2::TokenStream,
+}
+
+/// Pairs of function and group of its arguments, example: `sum(amount), group_by(user_id)`
+fn parse_calls(tokens: proc_macro2::TokenStream) -> Vec<(String, proc_macro2::TokenStream)> {
+    let mut calls = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let TokenTree::Ident(ident) = token else {
+            continue;
+        };
+        match tokens.peek() {
+            Some(TokenTree::Group(group)) => {
+                calls.push((ident.to_string(), group.stream()));
+                tokens.next();
+            }
+            _ => calls.push((ident.to_string(), proc_macro2::TokenStream::new())),
+        }
+    }
+    calls
+}
+
+pub fn sql_aggregate_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let error = table.get_error();
+    let struct_name = input.ident.clone();
+    let vis = input.vis.clone();
+    let from_clause = table.get_from_clause();
+    let executor = table.get_executor();
+    let (read_param, read) = (executor.read_param(), executor.read());
+
+    let fields = fields_named_struct(input);
+    let ff = extract_fields_as_params(fields);
+    let tenant = table.get_tenant(fields);
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+            .unwrap_or_else(|| panic!("Field {name} not contains in {struct_name}"))
+    };
+    let column = |name: &str| table.get_column(field(name)).expect("Named field");
+
+    let mut methods = vec![];
+    let mut structs = vec![];
+    for (method_name, tokens) in table.get_aggregates() {
+        let mut group_by = vec![];
+        let mut aggregates = vec![];
+        let mut filter_names = vec![];
+        for (function, args) in parse_calls(tokens) {
+            match function.as_str() {
+                "group_by" => {
+                    for (key, key_args) in parse_calls(args) {
+                        // Example: date_trunc_day(created_at)
+                        if let Some(unit) = key.strip_prefix("date_trunc_") {
+                            let name = group_idents(&TokenTree::Group(proc_macro2::Group::new(
+                                proc_macro2::Delimiter::Parenthesis,
+                                key_args,
+                            )))
+                            .pop()
+                            .expect("date_trunc must has field");
+                            let field = field(&name);
+                            let ty = &field.ty;
+                            let cast = match column_type(ty).0 {
+                                ColumnType::Named("Date") => "::date",
+                                _ => "",
+                            };
+                            group_by.push((
+                                AggregateColumn {
+                                    sql: format!("date_trunc('{unit}', {}){cast}", column(&name)),
+                                    name: format!("{name}_{unit}"),
+                                    ty: quote! { #ty },
+                                },
+                                option_inner(ty).is_some(),
+                            ));
+                        } else {
+                            let ty = &field(&key).ty;
+                            group_by.push((
+                                AggregateColumn {
+                                    sql: column(&key),
+                                    name: key.clone(),
+                                    ty: quote! { #ty },
+                                },
+                                option_inner(ty).is_some(),
+                            ));
+                        }
+                    }
+                }
+                "filter" => filter_names.extend(
+                    parse_calls(args)
+                        .into_iter()
+                        .map(|(name, _)| name)
+                        .collect::<Vec<_>>(),
+                ),
+                "count" => {
+                    let name = parse_calls(args).pop().map(|(name, _)| name);
+                    aggregates.push(match name {
+                        Some(name) => AggregateColumn {
+                            sql: format!("count({})", column(&name)),
+                            name: format!("count_{name}"),
+                            ty: quote! { i64 },
+                        },
+                        None => AggregateColumn {
+                            sql: "count(*)".to_string(),
+                            name: "count".to_string(),
+                            ty: quote! { i64 },
+                        },
+                    });
+                }
+                "sum" | "avg" | "min" | "max" => {
+                    let (name, _) = parse_calls(args)
+                        .pop()
+                        .unwrap_or_else(|| panic!("{function} must has field"));
+                    let ty = &field(&name).ty;
+                    let inner = option_inner(ty).unwrap_or(ty);
+                    let sql = format!("{function}({})", column(&name));
+                    let kind = match column_type(inner).0 {
+                        ColumnType::Named("SmallInt" | "Integer" | "BigInt") => "int",
+                        ColumnType::Named("Real" | "Double") => "float",
+                        _ => "other",
+                    };
+                    // Types of postgres results are casted to known rust types
+                    let (sql, ty) = match (function.as_str(), kind) {
+                        ("sum", "int") => (format!("{sql}::int8"), quote! { i64 }),
+                        ("sum" | "avg", "float") | ("avg", "int") => {
+                            (format!("{sql}::float8"), quote! { f64 })
+                        }
+                        _ => (sql, quote! { #inner }),
+                    };
+                    aggregates.push(AggregateColumn {
+                        sql,
+                        name: format!("{function}_{name}"),
+                        ty: quote! { Option<#ty> },
+                    });
+                }
+                function => panic!(
+                    "Unknown function {function} of aggregate {method_name}, expected sum, avg, min, max, count, group_by or filter"
+                ),
+            }
+        }
+
+        let result_name = format_ident!("{struct_name}{}", to_camel_case(&method_name));
+        // `!` and `?` set nullability of columns for query_as!
+        let mut sql_columns = group_by
+            .iter()
+            .map(|(column, nullable)| {
+                let mark = if *nullable { "?" } else { "!" };
+                format!("{} AS \"{}{mark}\"", column.sql, column.name)
+            })
+            .collect::<Vec<String>>();
+        sql_columns.extend(aggregates.iter().map(|column| {
+            let mark = if column.name.starts_with("count") {
+                "!"
+            } else {
+                "?"
+            };
+            format!("{} AS \"{}{mark}\"", column.sql, column.name)
+        }));
+        let result_fields = group_by
+            .iter()
+            .map(|(column, _)| column)
+            .chain(aggregates.iter())
+            .map(|column| {
+                let name = format_ident!("{}", column.name);
+                let ty = &column.ty;
+                quote! { pub #name: #ty }
+            })
+            .collect::<Vec<_>>();
+        structs.push(quote! {
+            #[derive(Debug, Clone)]
+            #vis struct #result_name {
+                #(#result_fields),*
+            }
+        });
+
+        let fields_with_type = ff
+            .clone()
+            .into_iter()
+            .filter(|(name_field, _)| filter_names.contains(&name_field.to_string()))
+            .collect::<Vec<_>>();
+        let params = get_method_params(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, &fields_with_type);
+        let filter_fields = get_filters(fields_with_type);
+        let sql_filters = if filter_fields.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " WHERE {}",
+                generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields))
+            )
+        };
+        let group_sql = group_by
+            .iter()
+            .map(|(column, _)| column.sql.clone())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let group_sql = if group_sql.is_empty() {
+            String::new()
+        } else {
+            format!(" GROUP BY {group_sql} ORDER BY {group_sql}")
+        };
+        let query = format!(
+            "SELECT {} FROM {from_clause}{sql_filters}",
+            sql_columns.join(", ")
+        );
+        // Example: revenue_by_day(pool, org_id) for `#[table(tenant = org_id)]`
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            !filter_fields.is_empty(),
+            &params,
+            &bind_params,
+        );
+        let query = format!("{query}{group_sql}");
+
+        let mn = format_ident!("{method_name}");
+        methods.push(quote! {
+            #[doc=#query]
+            pub async fn #mn(#read_param, #params) -> Result<Vec<#result_name>, #error> {
+                let object = sqlx::query_as!(
+                    #result_name,
+                    #query,
+                    #(#bind_params),*
+                )
+                .fetch_all(#read)
+                .await?;
+                Ok(object)
+            }
+        });
+    }
+
+    let token_stream = quote! {
+        #(#structs)*
+
+        impl #struct_name {
+            #(#methods)*
+        }
+    };
+    token_stream.into()
+}
@@ -0,0 +1,308 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+
+use crate::parser::{
+    RowType, Table, extract_fields_as_params, field_has_flag, fields_named_struct, get_bind_params,
+    get_filters, get_ident_bind, get_sql_columns, option_inner, parse_fields_with_type,
+    to_camel_case,
+};
+
+/// Implement `sql_macros::Crud` for struct with fields `#[table(primary_key)]`
+pub fn sql_crud_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let error = table.get_error();
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let table_name = table.get_name();
+    let from_clause = table.get_from_clause();
+    let fields = fields_named_struct(input);
+    let primary_key = parse_fields_with_type(fields, "primary_key");
+    if primary_key.is_empty() {
+        let message = format!("{struct_name} must have a field with #[table(primary_key)]");
+        return syn::Error::new_spanned(struct_name, message)
+            .to_compile_error()
+            .into();
+    }
+    // Methods of `Crud` have no tenant param and take pool instead of `RlsTransaction`,
+    // they would read and write rows of all tenants
+    if table.get_tenant(fields).is_some() || table.get_executor().rls {
+        let message = format!(
+            "Crud of {struct_name} can't be derived with #[table(tenant)] or #[table(rls)], methods of Crud have no tenant"
+        );
+        return syn::Error::new_spanned(struct_name, message)
+            .to_compile_error()
+            .into();
+    }
+
+    let pk_idents = get_filters(primary_key.clone());
+    let pk_types = primary_key.iter().map(|(_, ty)| ty);
+    let id_type = if primary_key.len() == 1 {
+        let ty = &primary_key[0].1;
+        quote! { #ty }
+    } else {
+        quote! { (#(#pk_types),*) }
+    };
+    let id_value = if primary_key.len() == 1 {
+        let pk = &pk_idents[0];
+        quote! { self.#pk.clone() }
+    } else {
+        quote! { (#(self.#pk_idents.clone()),*) }
+    };
+    let id_destruct = if primary_key.len() == 1 {
+        let pk = &pk_idents[0];
+        quote! { let #pk = id; }
+    } else {
+        quote! { let (#(#pk_idents),*) = id; }
+    };
+
+    let sql_columns = get_sql_columns(&table, fields).join(", ");
+    let row_type = RowType::new(struct_name, fields);
+    // Joined columns of view are not written, example: `#[table(source = users.email)]`
+    let written = |field: &&syn::Field| {
+        table
+            .get_column_source(field)
+            .is_none_or(|source| source.starts_with(&format!("{table_name}.")))
+    };
+    let columns = fields
+        .iter()
+        .filter(written)
+        .filter_map(|field| field.ident.clone())
+        .filter(|ident| !pk_idents.contains(ident))
+        .collect::<Vec<_>>();
+    // Key generated by database (serial, identity) is not inserted, example:
+    // `#[table(primary_key, generated)]`
+    let insert_idents = fields
+        .iter()
+        .filter(written)
+        .filter(|field| !field_has_flag(field, "generated"))
+        .filter_map(|field| field.ident.clone())
+        .collect::<Vec<_>>();
+
+    // Example: `r#type` is `type`, `#[table(source = orders.total)]` is `total`
+    let column_name = |ident: &proc_macro2::Ident| {
+        fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(ident))
+            .and_then(|field| table.get_column_name(field))
+            .expect("Field of struct")
+    };
+    let insert_columns = insert_idents
+        .iter()
+        .map(column_name)
+        .collect::<Vec<String>>()
+        .join(", ");
+    let insert_index = (1..=insert_idents.len())
+        .map(|index| format!("${index}"))
+        .collect::<Vec<String>>()
+        .join(",");
+    let set_columns = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| format!("{}=${}", column_name(column), index + 1))
+        .collect::<Vec<String>>()
+        .join(", ");
+    // Example: orders.id=$1 for `#[table(from = orders, join(..))]`
+    let sql_filters = |idents: &[proc_macro2::Ident], offset: usize| {
+        idents
+            .iter()
+            .map(|ident| {
+                fields
+                    .iter()
+                    .find(|field| field.ident.as_ref() == Some(ident))
+                    .and_then(|field| table.get_column_source(field))
+                    .unwrap_or_else(|| column_name(ident))
+            })
+            .enumerate()
+            .map(|(index, column)| format!("{column}=${}", offset + index + 1))
+            .collect::<Vec<String>>()
+            .join(" AND ")
+    };
+    let pk_filters = pk_idents
+        .iter()
+        .enumerate()
+        .map(|(index, column)| format!("{}=${}", column_name(column), index + 1))
+        .collect::<Vec<String>>()
+        .join(" AND ");
+    let update_pk_filters = pk_idents
+        .iter()
+        .enumerate()
+        .map(|(index, column)| format!("{}=${}", column_name(column), columns.len() + index + 1))
+        .collect::<Vec<String>>()
+        .join(" AND ");
+
+    let select_query = format!(
+        "SELECT {sql_columns} FROM {from_clause} WHERE {}",
+        sql_filters(&pk_idents, 0)
+    );
+    let select_all_query = format!("SELECT {sql_columns} FROM {from_clause}");
+    let insert_query =
+        format!("INSERT INTO {table_name} ({insert_columns}) VALUES ({insert_index})");
+    let update_query = format!("UPDATE {table_name} SET {set_columns} WHERE {update_pk_filters}");
+    // Joined columns of view are selected from written row, CTE has name of table, example:
+    // WITH orders AS (UPDATE orders .. RETURNING *) SELECT .. FROM orders JOIN users ON ..
+    let returning = |query: String| {
+        if table.get_joins().is_empty() {
+            format!("{query} RETURNING {sql_columns}")
+        } else {
+            format!(
+                "WITH {table_name} AS ({query} RETURNING *) SELECT {sql_columns} FROM {from_clause}"
+            )
+        }
+    };
+    let (insert_query, update_query) = (returning(insert_query), returning(update_query));
+    let delete_query = format!("DELETE FROM {table_name} WHERE {pk_filters}");
+    let find_by_id = row_type.query_as(&select_query, quote! { #(#pk_idents),* });
+    let find_all = row_type.query_as(&select_all_query, quote! {});
+    // Example: self.email as _, sqlx::types::Json(&self.settings) as _
+    let insert_binds = insert_idents
+        .iter()
+        .map(|ident| get_ident_bind(fields, ident));
+    let insert = row_type.query_as(&insert_query, quote! { #(#insert_binds),* });
+    let update_binds = columns.iter().map(|ident| get_ident_bind(fields, ident));
+    let update = row_type.query_as(
+        &update_query,
+        quote! { #(#update_binds,)* #(self.#pk_idents as _),* },
+    );
+
+    // Equality filters: fields `#[table(select)]`, `#[table(select_many)]`, `#[table(unique)]`,
+    // `#[table(index)]` and methods
+    // `#[table(select = method(fields))]`, `#[table(select_many = method(fields))]`
+    let mut eq_filters: Vec<(String, Vec<(proc_macro2::Ident, syn::Type)>)> = vec![];
+    for field_with_type in parse_fields_with_type(fields, "select")
+        .into_iter()
+        .chain(parse_fields_with_type(fields, "select_many"))
+        .chain(parse_fields_with_type(fields, "unique"))
+        .chain(parse_fields_with_type(fields, "index"))
+    {
+        eq_filters.push((field_with_type.0.unraw().to_string(), vec![field_with_type]));
+    }
+    let ff = extract_fields_as_params(fields);
+    for (method_name, method_fields) in table
+        .get_select()
+        .into_iter()
+        .chain(table.get_select_many())
+    {
+        let fields_with_type = ff
+            .clone()
+            .into_iter()
+            .filter(|(name_field, _)| method_fields.contains(&name_field.to_string()))
+            .collect::<Vec<_>>();
+        eq_filters.push((method_name, fields_with_type));
+    }
+    let mut variant_names: Vec<String> = vec![];
+    eq_filters.retain(|(name, fields_with_type)| {
+        let variant = to_camel_case(name);
+        if fields_with_type.is_empty() || variant_names.contains(&variant) {
+            return false;
+        }
+        variant_names.push(variant);
+        true
+    });
+
+    let eq_name = quote::format_ident!("{}Eq", struct_name);
+    let mut variants = vec![];
+    let mut find_arms = vec![];
+    let mut match_arms = vec![];
+    for (name, fields_with_type) in &eq_filters {
+        let variant = quote::format_ident!("{}", to_camel_case(name));
+        let types = fields_with_type.iter().map(|(_, ty)| ty);
+        let filter_fields = get_filters(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, fields_with_type);
+        let query = format!(
+            "SELECT {sql_columns} FROM {from_clause} WHERE {}",
+            sql_filters(&filter_fields, 0)
+        );
+        let select = row_type.query_as(&query, quote! { #(#bind_params),* });
+        variants.push(quote! { #variant(#(#types),*) });
+        find_arms.push(quote! {
+            #eq_name::#variant(#(#filter_fields),*) => Ok(#select.fetch_all(pool).await?)
+        });
+        // Example: self.email == *email, nullable_eq(self.team_id.as_ref(), team_id.as_ref())
+        let comparisons = fields_with_type.iter().map(|(ident, ty)| {
+            if option_inner(ty).is_some() {
+                quote! { sql_macros::nullable_eq(self.#ident.as_ref(), #ident.as_ref()) }
+            } else {
+                quote! { self.#ident == *#ident }
+            }
+        });
+        match_arms.push(quote! {
+            #eq_name::#variant(#(#filter_fields),*) => true #(&& #comparisons)*
+        });
+    }
+    // Reference to empty enum is non-empty type, deref it
+    let matched_filter = if eq_filters.is_empty() {
+        quote! { *filter }
+    } else {
+        quote! { filter }
+    };
+    let eq_doc =
+        format!("Equality filters of `{struct_name}`, see `sql_macros::Crud::find_many_by`");
+
+    let token_stream = quote! {
+        #[doc=#eq_doc]
+        #vis enum #eq_name {
+            #(#variants),*
+        }
+
+        impl sql_macros::Crud for #struct_name {
+            type Id = #id_type;
+            type Eq = #eq_name;
+            type Error = #error;
+
+            fn id(&self) -> Self::Id {
+                #id_value
+            }
+
+            #[doc=#select_query]
+            async fn find_by_id(pool: &sqlx::PgPool, id: Self::Id) -> Result<Option<#struct_name>, #error> {
+                #id_destruct
+                let object = #find_by_id.fetch_optional(pool).await?;
+                Ok(object)
+            }
+
+            #[doc=#select_all_query]
+            async fn find_all(pool: &sqlx::PgPool) -> Result<Vec<#struct_name>, #error> {
+                let object = #find_all.fetch_all(pool).await?;
+                Ok(object)
+            }
+
+            async fn find_many_by(pool: &sqlx::PgPool, filter: Self::Eq) -> Result<Vec<#struct_name>, #error> {
+                match filter {
+                    #(#find_arms),*
+                }
+            }
+
+            fn matches(&self, filter: &Self::Eq) -> bool {
+                match #matched_filter {
+                    #(#match_arms),*
+                }
+            }
+
+            #[doc=#insert_query]
+            async fn insert(&self, conn: &mut sqlx::PgConnection) -> Result<#struct_name, #error> {
+                let object = #insert.fetch_one(&mut *conn).await?;
+                Ok(object)
+            }
+
+            #[doc=#update_query]
+            async fn update(&self, conn: &mut sqlx::PgConnection) -> Result<#struct_name, #error> {
+                let object = #update.fetch_one(&mut *conn).await?;
+                Ok(object)
+            }
+
+            #[doc=#delete_query]
+            async fn delete(conn: &mut sqlx::PgConnection, id: Self::Id) -> Result<u64, #error> {
+                #id_destruct
+                let result = sqlx::query!(
+                    #delete_query,
+                    #(#pk_idents),*
+                )
+                .execute(&mut *conn)
+                .await?;
+                Ok(result.rows_affected())
+            }
+        }
+    };
+    token_stream.into()
+}
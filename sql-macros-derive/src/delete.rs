@@ -1,30 +1,166 @@
 use crate::parser::{
-    Table, extract_fields_as_params, fields_named_struct, generate_sql_params_condition,
-    get_filters, get_method_params, parse_fields_with_type,
+    RowType, Table, Tenant, extract_fields_as_params, fields_named_struct,
+    generate_sql_params_condition, get_bind_params, get_filters, get_method_params, is_custom_type,
+    parse_fields_with_type, required_method_name, scope_tenant,
 };
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 
 fn generate_method(
     method_name: &str,
-    _struct_name: &proc_macro2::Ident,
     params: &proc_macro2::TokenStream,
     query: &str,
-    filter_fields: &Vec<proc_macro2::Ident>,
+    filter_fields: &[proc_macro2::TokenStream],
+    table: &Table,
+    returning: &Option<(RowType, String)>,
+    unique: bool,
 ) -> proc_macro2::TokenStream {
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (write_param, write) = (executor.write_param(), executor.write());
     let mn =
         syn::parse_str::<proc_macro2::Ident>(method_name).expect("Failed to parse code string");
-    quote! {
-        #[doc=#query]
-        pub async fn #mn(conn: &mut sqlx::PgConnection, #params) -> Result<sqlx::any::AnyQueryResult, sqlx::Error> {
-            let result = sqlx::query!(
-                #query,
-                #(#filter_fields),*
-            )
-            .execute(&mut *conn)
-            .await?;
-            Ok(result.into()) // .into need for different db postgres and mysql
+    // Example: delete_by_id -> delete_one_by_id with `#[table(required)]`
+    let required_mn = required_method_name("delete", method_name);
+    let required = table.has_required();
+
+    let Some((type_param, returning)) = returning else {
+        let required_method = required.then(|| {
+            quote! {
+                /// Return error of `sqlx::Error::RowNotFound` if no rows are deleted
+                #[doc=#query]
+                pub async fn #required_mn(#write_param, #params) -> Result<sqlx::any::AnyQueryResult, #error> {
+                    let result = sqlx::query!(
+                        #query,
+                        #(#filter_fields),*
+                    )
+                    .execute(#write)
+                    .await?;
+                    if result.rows_affected() == 0 {
+                        return Err(sqlx::Error::RowNotFound.into());
+                    }
+                    Ok(result.into())
+                }
+            }
+        });
+        return quote! {
+            #[doc=#query]
+            pub async fn #mn(#write_param, #params) -> Result<sqlx::any::AnyQueryResult, #error> {
+                let result = sqlx::query!(
+                    #query,
+                    #(#filter_fields),*
+                )
+                .execute(#write)
+                .await?;
+                Ok(result.into()) // .into need for different db postgres and mysql
+            }
+
+            #required_method
+        };
+    };
+
+    let query = format!("{query} RETURNING {returning}");
+    let delete = type_param.query_as(&query, quote! { #(#filter_fields),* });
+    if unique {
+        // Filter by primary key or unique column deletes one row
+        let required_method = required.then(|| {
+            quote! {
+                /// Return error of `sqlx::Error::RowNotFound` if no rows are deleted
+                #[doc=#query]
+                pub async fn #required_mn(#write_param, #params) -> Result<#type_param, #error> {
+                    let object = #delete.fetch_one(#write).await?;
+                    Ok(object)
+                }
+            }
+        });
+        quote! {
+            #[doc=#query]
+            pub async fn #mn(#write_param, #params) -> Result<Option<#type_param>, #error> {
+                let object = #delete.fetch_optional(#write).await?;
+                Ok(object)
+            }
+
+            #required_method
+        }
+    } else {
+        let required_method = required.then(|| {
+            quote! {
+                /// Return error of `sqlx::Error::RowNotFound` if no rows are deleted
+                #[doc=#query]
+                pub async fn #required_mn(#write_param, #params) -> Result<Vec<#type_param>, #error> {
+                    let object = #delete.fetch_all(#write).await?;
+                    if object.is_empty() {
+                        return Err(sqlx::Error::RowNotFound.into());
+                    }
+                    Ok(object)
+                }
+            }
+        });
+        quote! {
+            #[doc=#query]
+            pub async fn #mn(#write_param, #params) -> Result<Vec<#type_param>, #error> {
+                let object = #delete.fetch_all(#write).await?;
+                Ok(object)
+            }
+
+            #required_method
+        }
+    }
+}
+
+fn generate_many_method(
+    field_with_type: &(proc_macro2::Ident, syn::Type),
+    table: &Table,
+    returning: &Option<(RowType, String)>,
+    custom_type: bool,
+    tenant: Option<&Tenant>,
+) -> proc_macro2::TokenStream {
+    let (field, field_type) = field_with_type;
+    let table_name = table.get_name();
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (write_param, write) = (executor.write_param(), executor.write());
+    // Example: values as _ for array of enum
+    let values = if custom_type {
+        quote! { values as _ }
+    } else {
+        quote! { values }
+    };
+    // Example: `r#type` is column `type` of method `delete_many_by_type`
+    let column = field.unraw();
+    let mn = syn::parse_str::<proc_macro2::Ident>(&format!("delete_many_by_{column}"))
+        .expect("Failed to parse code string");
+    let query = format!("DELETE FROM {table_name} WHERE {column} = ANY($1)");
+    let (query, params, binds) = scope_tenant(
+        tenant,
+        &query,
+        true,
+        &quote! { values: &[#field_type] },
+        &[values],
+    );
+
+    match returning {
+        Some((type_param, returning)) => {
+            let query = format!("{query} RETURNING {returning}");
+            let delete = type_param.query_as(&query, quote! { #(#binds),* });
+            quote! {
+                #[doc=#query]
+                pub async fn #mn(#write_param, #params) -> Result<Vec<#type_param>, #error> {
+                    let object = #delete.fetch_all(#write).await?;
+                    Ok(object)
+                }
+            }
         }
+        None => quote! {
+            #[doc=#query]
+            pub async fn #mn(#write_param, #params) -> Result<sqlx::any::AnyQueryResult, #error> {
+                let result = sqlx::query!(#query, #(#binds),*)
+                    .execute(#write)
+                    .await?;
+                Ok(result.into())
+            }
+        },
     }
 }
 
@@ -34,21 +170,57 @@ pub fn sql_delete_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table_name = table.get_name();
 
     let fields = fields_named_struct(input);
+    let tenant = table.get_tenant(fields);
     let fields_with_type = parse_fields_with_type(fields, "delete");
 
+    let returning = RowType::returning(&table, &struct_name, fields).map(|type_param| {
+        let returning = table.get_returning(&struct_name, fields);
+        (type_param, returning)
+    });
+    // Filter by one of these fields deletes at most one row
+    let unique_fields = get_filters(
+        [
+            parse_fields_with_type(fields, "primary_key"),
+            parse_fields_with_type(fields, "unique"),
+        ]
+        .concat(),
+    );
+
     let mut methods = vec![];
     for field_with_type in fields_with_type {
         let params = get_method_params(vec![field_with_type.clone()]);
         let filter_fields = get_filters(vec![field_with_type.clone()]);
-        let sql_filters = generate_sql_params_condition(&filter_fields);
+        let sql_filters =
+            generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields));
 
         let query = format!("DELETE FROM {table_name} WHERE {sql_filters}");
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &params,
+            &get_bind_params(fields, std::slice::from_ref(&field_with_type)),
+        );
         methods.push(generate_method(
-            &format!("delete_by_{}", field_with_type.0),
-            &struct_name,
+            &format!("delete_by_{}", field_with_type.0.unraw()),
             &params,
             &query,
-            &filter_fields,
+            &bind_params,
+            &table,
+            &returning,
+            filter_fields
+                .iter()
+                .any(|field| unique_fields.contains(field)),
+        ));
+        methods.push(generate_many_method(
+            &field_with_type,
+            &table,
+            &returning,
+            fields
+                .iter()
+                .find(|field| field.ident.as_ref() == Some(&field_with_type.0))
+                .is_some_and(is_custom_type),
+            tenant.as_ref(),
         ));
     }
 
@@ -66,16 +238,24 @@ pub fn sql_delete_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
             )
         }
         let params = get_method_params(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, &fields_with_type);
         let filter_fields = get_filters(fields_with_type);
-        let sql_filters = generate_sql_params_condition(&filter_fields);
+        let sql_filters =
+            generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields));
         let query = format!("DELETE FROM {table_name} WHERE {sql_filters}");
 
+        let (query, params, bind_params) =
+            scope_tenant(tenant.as_ref(), &query, true, &params, &bind_params);
         methods.push(generate_method(
             &method_name,
-            &struct_name,
             &params,
             &query,
-            &filter_fields,
+            &bind_params,
+            &table,
+            &returning,
+            filter_fields
+                .iter()
+                .any(|field| unique_fields.contains(field)),
         ));
     }
 
@@ -0,0 +1,132 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+use crate::parser::{Table, fields_named_struct, get_runtime_columns, is_json, option_inner};
+use crate::schema::{ColumnType, column_type};
+
+/// Criterion of field in filter struct
+enum Criterion {
+    /// `Option<sql_macros::StringFilter>`
+    String,
+    /// `Option<sql_macros::Range<T>>`
+    Range,
+    /// `Option<T>` compared by `=`
+    Eq,
+}
+
+fn criterion(ty: &syn::Type) -> Option<Criterion> {
+    match column_type(ty).0 {
+        ColumnType::Named("Text") => Some(Criterion::String),
+        ColumnType::Named(
+            "SmallInt" | "Integer" | "BigInt" | "Real" | "Double" | "Numeric" | "Date" | "Time"
+            | "Timestamp" | "TimestampTz",
+        ) => Some(Criterion::Range),
+        ColumnType::Named("Boolean" | "Uuid") | ColumnType::Custom(_) => Some(Criterion::Eq),
+        // Json, bytes and arrays can't be compared
+        _ => None,
+    }
+}
+
+pub fn sql_filter_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let error = table.get_error();
+    let struct_name = input.ident.clone();
+    let vis = input.vis.clone();
+    let filter_name = format_ident!("{struct_name}Filter");
+    let from_clause = table.get_from_clause();
+    let executor = table.get_executor();
+    let (read_param, read) = (executor.read_param(), executor.read());
+
+    let fields = fields_named_struct(input);
+    let sql_columns = get_runtime_columns(&table, fields).join(", ");
+    let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE TRUE");
+
+    let mut filter_fields = vec![];
+    let mut conditions = vec![];
+    // Example: filter(pool, &filter, &order, page, org_id) for `#[table(tenant = org_id)]`
+    let tenant = table.get_tenant(fields);
+    let tenant_params = tenant.as_ref().map(|tenant| tenant.param());
+    if let Some(tenant) = &tenant {
+        let ident = &tenant.ident;
+        let sql = format!(" AND {} = ", tenant.column);
+        conditions.push(quote! {
+            builder.push(#sql).push_bind(#ident);
+        });
+    }
+    let mut order_columns = vec![];
+    for field in fields {
+        let Some(ident) = field.ident.clone() else {
+            continue;
+        };
+        // Json can't be compared or sorted
+        if is_json(field) {
+            continue;
+        }
+        let column = table.get_column(field).expect("Named field");
+        let name = ident.unraw().to_string();
+        order_columns.push(quote! { (#name, #column) });
+
+        let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+        match criterion(ty) {
+            Some(Criterion::String) => {
+                filter_fields.push(quote! { pub #ident: Option<sql_macros::StringFilter> });
+                conditions.push(quote! {
+                    if let Some(criterion) = &filter.#ident {
+                        criterion.push_sql(#column, &mut builder);
+                    }
+                });
+            }
+            Some(Criterion::Range) => {
+                filter_fields.push(quote! { pub #ident: Option<sql_macros::Range<#ty>> });
+                conditions.push(quote! {
+                    if let Some(criterion) = &filter.#ident {
+                        criterion.push_sql(#column, &mut builder);
+                    }
+                });
+            }
+            Some(Criterion::Eq) => {
+                let sql = format!(" AND {column} = ");
+                filter_fields.push(quote! { pub #ident: Option<#ty> });
+                conditions.push(quote! {
+                    if let Some(value) = &filter.#ident {
+                        builder.push(#sql).push_bind(value);
+                    }
+                });
+            }
+            None => {}
+        }
+    }
+
+    let token_stream = quote! {
+        /// Optional criteria of `filter`, missing criterion is not checked
+        #[derive(Default)]
+        #vis struct #filter_name {
+            #(#filter_fields),*
+        }
+
+        impl #struct_name {
+            #[doc=#query]
+            pub async fn filter(
+                #read_param,
+                filter: &#filter_name,
+                order: &[sql_macros::OrderBy],
+                page: Option<sql_macros::Page>,
+                #tenant_params
+            ) -> Result<Vec<#struct_name>, #error> {
+                let mut builder = sqlx::QueryBuilder::<sqlx::Postgres>::new(#query);
+                #(#conditions)*
+                sql_macros::push_order_by(&mut builder, order, &[#(#order_columns),*])?;
+                if let Some(page) = page {
+                    page.push_sql(&mut builder);
+                }
+                let object = builder
+                    .build_query_as::<#struct_name>()
+                    .fetch_all(#read)
+                    .await?;
+                Ok(object)
+            }
+        }
+    };
+    token_stream.into()
+}
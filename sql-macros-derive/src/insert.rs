@@ -1,48 +1,55 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
-use crate::parser::{Table, fields_named_struct, get_sql_columns};
+use crate::parser::{RowType, Table, fields_named_struct, get_field_bind, get_struct_fields};
+
+/// `INSERT INTO {table_name} (columns) VALUES ($1,$2,..,values)`, columns are bound by position,
+/// values are sql expressions for rest of columns, example: `'queued'`
+pub fn generate_insert_query(table_name: &str, columns: &[String], values: &[String]) -> String {
+    let sql_column_index = (1..=columns.len() - values.len())
+        .map(|index| format!("${index}"))
+        .chain(values.iter().cloned())
+        .collect::<Vec<String>>()
+        .join(",");
+    format!(
+        "INSERT INTO {table_name} ({}) VALUES ({sql_column_index})",
+        columns.join(", ")
+    )
+}
 
 pub fn sql_insert_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table = Table::parse(input);
+    let error = table.get_error();
     let struct_name = input.ident.clone();
     let table_name = table.get_name();
+    let executor = table.get_executor();
+    let (write_param, write) = (executor.write_param(), executor.write());
 
     let fields = fields_named_struct(input);
 
-    let sql_columns = get_sql_columns(fields).join(", ");
-    let idents = fields
-        .iter()
-        .filter(|field| field.ident.is_some())
-        .map(|field| field.clone().ident.unwrap());
-    let sql_column_index = idents
-        .clone()
-        .enumerate()
-        .map(|(index, _)| format!("${}", index + 1))
-        .collect::<Vec<String>>()
-        .join(",");
-
-    let query = format!("INSERT INTO {table_name} ({sql_columns}) VALUES ({sql_column_index})");
-    let returning = table.get_return_fields().unwrap_or("*".to_string());
+    let sql_columns = get_struct_fields(fields);
+    // Tenant of row is checked by param of method, example: insert(&self, conn, org_id)
+    let tenant = table.get_tenant(fields);
+    let tenant_params = tenant.as_ref().map(|tenant| tenant.param());
+    let tenant_check = tenant
+        .as_ref()
+        .map(|tenant| tenant.check_field(&struct_name));
+    let binds = fields.iter().map(get_field_bind).collect::<Vec<_>>();
 
-    let token_stream = if let Some(return_type) = table.get_return_type() {
-        let type_param =
-            syn::parse_str::<syn::Type>(&return_type).expect("Failed to parse input string");
+    let query = generate_insert_query(&table_name, &sql_columns, &[]);
+    let returning = table.get_returning(&struct_name, fields);
 
+    let token_stream = if let Some(type_param) = RowType::returning(&table, &struct_name, fields) {
         let query = format!("{query} RETURNING {returning}");
+        let insert = type_param.query_as(&query, quote! { #(#binds),* });
 
         quote! {
             impl #struct_name {
                 #[doc=#query]
-                pub async fn insert(&self, conn: &mut sqlx::PgConnection) -> Result<#type_param, sqlx::Error>
+                pub async fn insert(&self, #write_param, #tenant_params) -> Result<#type_param, #error>
                 {
-                    let object = sqlx::query_as!(
-                        #type_param,
-                        #query,
-                        #(self.#idents as _),* // Example: fields as _
-                    )
-                    .fetch_one(&mut *conn)
-                    .await?;
+                    #tenant_check
+                    let object = #insert.fetch_one(#write).await?;
                     Ok(object)
                 }
             }
@@ -51,13 +58,14 @@ pub fn sql_insert_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
         quote! {
             impl #struct_name {
                 #[doc=#query]
-                pub async fn insert(&self, conn: &mut sqlx::PgConnection) -> Result<sqlx::any::AnyQueryResult, sqlx::Error>
+                pub async fn insert(&self, #write_param, #tenant_params) -> Result<sqlx::any::AnyQueryResult, #error>
                 {
+                    #tenant_check
                     let query_result = sqlx::query!(
                         #query,
-                        #(self.#idents as _),* // Example: fields as _
+                        #(#binds),* // Example: fields as _
                     )
-                    .execute(&mut *conn)
+                    .execute(#write)
                     .await?;
                     Ok(query_result.into())
                 }
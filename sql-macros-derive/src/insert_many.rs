@@ -1,20 +1,33 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
-use crate::parser::{Table, fields_named_struct, get_sql_columns};
+use crate::parser::{Table, fields_named_struct, get_struct_fields};
 
 pub fn sql_insert_many_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table = Table::parse(input);
+    let error = table.get_error();
     let struct_name = input.ident.clone();
     let table_name = table.get_name();
+    let executor = table.get_executor();
+    let (write_param, write) = (executor.write_param(), executor.write());
 
     let fields = fields_named_struct(input);
 
-    let sql_columns = get_sql_columns(fields).join(", ");
+    let sql_columns = get_struct_fields(fields).join(", ");
     let idents = fields
         .iter()
         .filter(|field| field.ident.is_some())
         .map(|field| field.clone().ident.unwrap());
+    // Tenant of row is checked by param of method, example: insert(&self, conn, org_id)
+    let tenant = table.get_tenant(fields);
+    let tenant_params = tenant.as_ref().map(|tenant| tenant.param());
+    let tenant_check = tenant
+        .as_ref()
+        .map(|tenant| tenant.check_field(&struct_name));
+    let values = idents
+        .clone()
+        .map(|ident| quote! { &self.#ident })
+        .collect::<Vec<_>>();
     let sql_column_index = idents
         .clone()
         .enumerate()
@@ -29,15 +42,16 @@ pub fn sql_insert_many_macro_derive(input: &mut syn::DeriveInput) -> TokenStream
     let token_stream = quote! {
         impl #struct_name {
             #[doc=#query]
-            pub async fn insert<T>(&self, conn: &mut sqlx::PgConnection) -> Result<(), sqlx::Error>
+            pub async fn insert<T>(&self, #write_param, #tenant_params) -> Result<(), #error>
             where
                 T: Send + Unpin + for<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow>
             {
+                #tenant_check
                 let object = sqlx::query_as::<_, T>(#query)
                 #(
-                    .bind(&self.#idents)
+                    .bind(#values)
                 )*
-                    .fetch_one(&mut *conn)
+                    .fetch_one(#write)
                     .await?;
                 Ok(())
             }
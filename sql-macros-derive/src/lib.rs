@@ -1,9 +1,16 @@
 use proc_macro::TokenStream;
 
+mod aggregate;
+mod crud;
 mod delete;
+mod filter;
 mod insert;
 mod insert_many;
 mod parser;
+mod queue;
+mod relations;
+mod schema;
+mod search;
 mod select;
 mod select_all;
 mod select_many;
@@ -52,6 +59,48 @@ pub fn sql_delete_macro_derive(input: TokenStream) -> TokenStream {
     delete::sql_delete_macro_derive(&mut input)
 }
 
+#[proc_macro_derive(SqlRelations, attributes(table))]
+pub fn sql_relations_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    relations::sql_relations_macro_derive(&mut input)
+}
+
+#[proc_macro_derive(SqlAggregate, attributes(table))]
+pub fn sql_aggregate_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    aggregate::sql_aggregate_macro_derive(&mut input)
+}
+
+#[proc_macro_derive(SqlSearch, attributes(table))]
+pub fn sql_search_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    search::sql_search_macro_derive(&mut input)
+}
+
+#[proc_macro_derive(SqlFilter, attributes(table))]
+pub fn sql_filter_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    filter::sql_filter_macro_derive(&mut input)
+}
+
+#[proc_macro_derive(SqlQueue, attributes(table))]
+pub fn sql_queue_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    queue::sql_queue_macro_derive(&mut input)
+}
+
+#[proc_macro_derive(SqlCrud, attributes(table))]
+pub fn sql_crud_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    crud::sql_crud_macro_derive(&mut input)
+}
+
+#[proc_macro_derive(SqlSchema, attributes(table))]
+pub fn sql_schema_macro_derive(input: TokenStream) -> TokenStream {
+    let mut input = syn::parse_macro_input!(input as syn::DeriveInput);
+    schema::sql_schema_macro_derive(&mut input)
+}
+
 #[proc_macro_derive(SqlTable, attributes(table))]
 pub fn sql_table_macro_derive(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
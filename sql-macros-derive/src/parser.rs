@@ -1,7 +1,10 @@
 use proc_macro2::{Ident, TokenTree};
-use quote::ToTokens;
+use quote::{ToTokens, quote};
+use syn::ext::IdentExt;
 use syn::{Meta, MetaList, PathArguments, Type};
 
+use crate::schema::{ColumnType, column_type};
+
 fn syntax_tree_to_string(ty: &Type) -> String {
     match ty {
         Type::Path(type_path) => {
@@ -95,7 +98,7 @@ pub fn get_filters(fields_with_type: Vec<(Ident, syn::Type)>) -> Vec<proc_macro2
         .collect::<Vec<Ident>>()
 }
 
-pub fn generate_sql_params_condition(name_params: &[proc_macro2::Ident]) -> String {
+pub fn generate_sql_params_condition<T: std::fmt::Display>(name_params: &[T]) -> String {
     name_params
         .iter()
         .enumerate()
@@ -105,12 +108,15 @@ pub fn generate_sql_params_condition(name_params: &[proc_macro2::Ident]) -> Stri
 }
 
 pub fn get_sql_columns(
+    table: &Table,
     fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
 ) -> Vec<String> {
     fields
         .iter()
         .filter_map(|field| {
-            let field_name = field.clone().ident.as_ref()?.to_string();
+            let field_name = field.ident.as_ref()?.unraw().to_string();
+            // Example: "users.email" for struct with `#[table(from = orders, join(users, ..))]`
+            let column = table.get_column_source(field).unwrap_or(field_name.clone());
 
             for attr in &field.attrs {
                 if !attr.path().is_ident("table") {
@@ -122,33 +128,239 @@ pub fn get_sql_columns(
 
                     for token in meta_list.clone().tokens.into_iter() {
                         match token {
-                            TokenTree::Ident(ident) => {
-                                if ident == "as_type" {
-                                    current_key = true;
-                                }
+                            TokenTree::Ident(ident) if ident == "as_type" => {
+                                current_key = true;
                             }
                             TokenTree::Literal(literal) => {
                                 if !current_key {
                                     continue;
                                 }
-                                return Some(format!("{field_name} AS {literal}")); // Example: "email, role AS \"role!: Role\""
+                                return Some(format!("{column} AS {literal}")); // Example: "email, role AS \"role!: Role\""
                             }
                             _ => {}
                         }
                     }
                 }
             }
-            Some(field_name)
+            if let Some(type_override) = type_override(field) {
+                return Some(format!("{column} AS \"{field_name}{type_override}\"")); // Example: "role AS \"role!: Role\""
+            }
+            runtime_column(table, field)
         })
         .collect()
 }
 
+/// Column of field with alias of view, example: "users.email AS author_email"
+fn runtime_column(table: &Table, field: &syn::Field) -> Option<String> {
+    let field_name = field.ident.as_ref()?.unraw().to_string();
+    let column = table.get_column_source(field).unwrap_or(field_name.clone());
+    if column == field_name || column.ends_with(&format!(".{field_name}")) {
+        Some(column)
+    } else {
+        Some(format!("{column} AS {field_name}"))
+    }
+}
+
+/// Field of postgres enum or other custom type: `#[table(sql_type)]`, `#[table(sql_type = "user_role")]`
+/// or type unknown for `SqlSchema`, example: `Role`, `Option<Role>`, `Vec<Role>`
+pub fn is_custom_type(field: &syn::Field) -> bool {
+    field_has_flag(field, "sql_type")
+        || get_sql_type(field).is_some()
+        || is_custom_column(&field.ty)
+}
+
+/// Name of database type of `#[table(sql_type = "user_role")]`
+pub fn get_sql_type(field: &syn::Field) -> Option<String> {
+    get_field_kind_str(field, "sql_type")
+}
+
+fn is_custom_column(ty: &syn::Type) -> bool {
+    match column_type(ty).0 {
+        ColumnType::Custom(_) => true,
+        ColumnType::Array(inner) => matches!(*inner, ColumnType::Custom(_)),
+        ColumnType::Named(_) => false,
+    }
+}
+
+/// Override of `query_as!` for custom type, example: `!: Role`, `?: Role` for `Option<Role>`,
+/// `!: sqlx::types::Json<Settings>` for `#[table(json)]`
+fn type_override(field: &syn::Field) -> Option<String> {
+    if is_json(field) {
+        return match option_inner(&field.ty) {
+            Some(inner) => Some(format!("?: sqlx::types::Json<{}>", inner.to_token_stream())),
+            None => Some(format!(
+                "!: sqlx::types::Json<{}>",
+                field.ty.to_token_stream()
+            )),
+        };
+    }
+    if !is_custom_type(field) {
+        return None;
+    }
+    match option_inner(&field.ty) {
+        Some(inner) => Some(format!("?: {}", syntax_tree_to_string(inner))),
+        None => Some(format!("!: {}", syntax_tree_to_string(&field.ty))),
+    }
+}
+
+/// Field of serde type stored in json column `#[table(json)]`
+pub fn is_json(field: &syn::Field) -> bool {
+    field_has_flag(field, "json")
+}
+
+/// Value of field of `self` for `query!`, example: `self.email as _`,
+/// `sqlx::types::Json(&self.settings) as _` for `#[table(json)]`
+pub fn get_field_bind(field: &syn::Field) -> proc_macro2::TokenStream {
+    let ident = field
+        .ident
+        .as_ref()
+        .expect("Struct must by named type struct");
+    match (is_json(field), option_inner(&field.ty)) {
+        (true, Some(_)) => quote! { self.#ident.as_ref().map(sqlx::types::Json) as _ },
+        (true, None) => quote! { sqlx::types::Json(&self.#ident) as _ },
+        _ => quote! { self.#ident as _ },
+    }
+}
+
+/// Bind of field found by name, see `get_field_bind`
+pub fn get_ident_bind(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ident: &Ident,
+) -> proc_macro2::TokenStream {
+    fields
+        .iter()
+        .find(|field| field.ident.as_ref() == Some(ident))
+        .map(get_field_bind)
+        .unwrap_or_else(|| panic!("Field {ident} not contains in struct"))
+}
+
+/// Output of generated `query_as!`, rows of struct with `#[table(json)]` fields are mapped
+/// from `query!` because `sqlx::types::Json<T>` can't be converted into `T` by `query_as!`
+pub struct RowType {
+    name: proc_macro2::TokenStream,
+    idents: Vec<Ident>,
+    /// Json fields and flag of `Option`
+    json_fields: Vec<(Ident, bool)>,
+}
+
+impl RowType {
+    pub fn new(
+        name: impl ToTokens,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ) -> Self {
+        Self {
+            name: name.to_token_stream(),
+            idents: fields
+                .iter()
+                .filter_map(|field| field.ident.clone())
+                .collect(),
+            json_fields: fields
+                .iter()
+                .filter(|field| is_json(field))
+                .filter_map(|field| Some((field.ident.clone()?, option_inner(&field.ty).is_some())))
+                .collect(),
+        }
+    }
+
+    /// Type of `#[table(return_type = ...)]`, struct itself for `Self` or other type with
+    /// returned fields of struct, json fields are mapped for both
+    pub fn returning(
+        table: &Table,
+        struct_name: &Ident,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ) -> Option<Self> {
+        let return_type = table.get_return_type()?;
+        let mut row_type = if return_type == "Self" || *struct_name == return_type {
+            Self::new(struct_name, fields)
+        } else {
+            let name =
+                syn::parse_str::<syn::Type>(&return_type).expect("Failed to parse input string");
+            Self::new(name, fields)
+        };
+        // Example: `#[table(return_fields = "id, settings")]`
+        if let Some(return_fields) = table.get_return_fields() {
+            let names = return_fields
+                .split(',')
+                .map(|name| name.trim())
+                .collect::<Vec<&str>>();
+            row_type
+                .idents
+                .retain(|ident| names.contains(&ident.unraw().to_string().as_str()));
+            row_type
+                .json_fields
+                .retain(|(ident, _)| names.contains(&ident.unraw().to_string().as_str()));
+        }
+        Some(row_type)
+    }
+
+    /// Example: `sqlx::query_as!(User, "SELECT ...", id)`
+    pub fn query_as(
+        &self,
+        query: &str,
+        args: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        if self.json_fields.is_empty() {
+            return quote! { sqlx::query_as!(#name, #query, #args) };
+        }
+        let values = self.idents.iter().map(|ident| {
+            match self.json_fields.iter().find(|(json, _)| json == ident) {
+                Some((_, true)) => quote! { #ident: row.#ident.map(|json| json.0) },
+                Some((_, false)) => quote! { #ident: row.#ident.0 },
+                None => quote! { #ident: row.#ident },
+            }
+        });
+        quote! {
+            sqlx::query!(#query, #args).map(|row| #name { #(#values),* })
+        }
+    }
+}
+
+impl ToTokens for RowType {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.name.to_tokens(tokens);
+    }
+}
+
+/// Params of `query!`, custom types are bound as `role as _` because `sqlx` can't check them
+pub fn get_bind_params(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    fields_with_type: &[(Ident, syn::Type)],
+) -> Vec<proc_macro2::TokenStream> {
+    fields_with_type
+        .iter()
+        .map(|(name_param, type_param)| {
+            let custom = fields
+                .iter()
+                .find(|field| field.ident.as_ref() == Some(name_param))
+                .map_or_else(|| is_custom_column(type_param), is_custom_type);
+            if custom {
+                quote! { #name_param as _ }
+            } else {
+                quote! { #name_param }
+            }
+        })
+        .collect()
+}
+
+/// Columns for queries built at runtime, `as_type` overrides of `query_as!` are skipped,
+/// example: "posts.id, users.email AS author_email"
+pub fn get_runtime_columns(
+    table: &Table,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> Vec<String> {
+    fields
+        .iter()
+        .filter_map(|field| runtime_column(table, field))
+        .collect()
+}
+
 pub fn get_struct_fields(
     fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
 ) -> Vec<String> {
     fields
         .iter()
-        .filter_map(|field| Some(field.clone().ident.as_ref()?.to_string()))
+        .filter_map(|field| Some(field.ident.as_ref()?.unraw().to_string()))
         .collect()
 }
 
@@ -171,6 +383,122 @@ pub fn extract_fields_as_params(
     stamp
 }
 
+/// Convert `snake_case` name to `CamelCase`, example: `is_active` -> `IsActive`
+pub fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Convert `CamelCase` name to `snake_case`, example: `OrderStatus` -> `order_status`
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, char) in name.chars().enumerate() {
+        if char.is_uppercase() {
+            if index != 0 {
+                snake.push('_');
+            }
+            snake.extend(char.to_lowercase());
+        } else {
+            snake.push(char);
+        }
+    }
+    snake
+}
+
+/// Name of method which returns `sqlx::Error::RowNotFound` instead of `None` or empty result,
+/// example: `select_by_id` -> `select_one_by_id`, `delete_removed` -> `delete_one_removed`,
+/// `active_users` -> `active_users_one`
+pub fn required_method_name(verb: &str, method_name: &str) -> Ident {
+    let name = match method_name.strip_prefix(verb) {
+        Some(suffix) if suffix.is_empty() || suffix.starts_with('_') => {
+            format!("{verb}_one{suffix}")
+        }
+        _ => format!("{method_name}_one"),
+    };
+    syn::parse_str::<Ident>(&name).expect("Failed to parse code string")
+}
+
+/// English plural of snake case name, example: `post` -> `posts`, `category` -> `categories`,
+/// `address` -> `addresses`, irregular plural is set by `#[table(has_many = Person, plural = people)]`
+pub fn pluralize(name: &str) -> String {
+    if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        return format!("{name}es");
+    }
+    if let Some(stem) = name.strip_suffix('y')
+        && !stem.ends_with(['a', 'e', 'i', 'o', 'u'])
+    {
+        return format!("{stem}ies");
+    }
+    format!("{name}s")
+}
+
+fn field_meta_list(field: &syn::Field) -> impl Iterator<Item = &MetaList> {
+    field.attrs.iter().filter_map(|attr| match &attr.meta {
+        Meta::List(meta) if meta.path.is_ident("table") => Some(meta),
+        _ => None,
+    })
+}
+
+/// Check field has flag `#[table(some_flag)]`
+pub fn field_has_flag(field: &syn::Field, flag: &str) -> bool {
+    field_meta_list(field).any(|meta| meta_has_flag(meta, flag))
+}
+
+/// Check `#[table(some_flag)]` contains flag without value
+fn meta_has_flag(meta: &MetaList, flag: &str) -> bool {
+    let mut tokens = meta.tokens.clone().into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if let TokenTree::Ident(ident) = token
+            && ident == flag
+            && !matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '=')
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Parse field `#[table(some_ident = some_value)]` by some_ident and return Some(some_value)
+pub fn get_field_kind_str(field: &syn::Field, by_ident: &str) -> Option<String> {
+    field_meta_list(field).find_map(|meta| get_kind_str(meta, by_ident))
+}
+
+/// Parse field `#[table(some_ident = some::path)]` by some_ident and return Some("some::path"),
+/// tokens are read until comma
+pub fn get_field_kind_path(field: &syn::Field, by_ident: &str) -> Option<String> {
+    field_meta_list(field).find_map(|meta| get_kind_path(meta, by_ident))
+}
+
+/// Parse `#[table(some_ident = some::path)]` by some_ident and return Some("some::path")
+fn get_kind_path(meta: &MetaList, by_ident: &str) -> Option<String> {
+    let mut tokens = meta.tokens.clone().into_iter();
+    while let Some(token) = tokens.next() {
+        if !matches!(&token, TokenTree::Ident(ident) if ident == by_ident) {
+            continue;
+        }
+        if !matches!(tokens.next(), Some(TokenTree::Punct(punct)) if punct.as_char() == '=') {
+            continue;
+        }
+        let path = tokens
+            .by_ref()
+            .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+            .map(|token| token_to_string(&token))
+            .collect::<String>();
+        return Some(path);
+    }
+    None
+}
+
 /// Parse `#[table(some_ident = some_value)]` by some_ident and return Some(some_value)
 fn get_kind_str(meta: &MetaList, by_ident: &str) -> Option<String> {
     let mut kind: Option<String> = None;
@@ -191,14 +519,21 @@ fn get_kind_str(meta: &MetaList, by_ident: &str) -> Option<String> {
             }
             TokenTree::Literal(literal) => {
                 if read_next_literal {
-                    kind = Some(literal.to_string());
+                    // Example: "id, role AS \"role!: Role\"" -> id, role AS "role!: Role"
+                    kind = Some(
+                        syn::parse_str::<syn::LitStr>(&literal.to_string())
+                            .map(|literal| literal.value())
+                            .unwrap_or(literal.to_string()),
+                    );
                     break;
                 }
             }
+            // Flag without value, example: `#[table(sql_type, unique)]`
+            TokenTree::Punct(punct) if punct.as_char() == ',' => read_next_literal = false,
             _ => continue,
         }
     }
-    kind.map(|kind| kind.replace('"', ""))
+    kind
 }
 
 /// Parse `#[table(some_ident = some_method(field1, field1))]` by some_ident and return Some((some_method, vec![field1, field1]))
@@ -243,6 +578,477 @@ fn get_kind_method_with_params(meta: &MetaList, by_ident: &str) -> Option<(Strin
     kind
 }
 
+/// Parse `#[table(some_ident = some_method(field1) -> SomeType)]` by some_ident and return
+/// Some((some_method, "SomeType"))
+fn get_kind_method_projection(meta: &MetaList, by_ident: &str) -> Option<(String, String)> {
+    let mut tokens = meta.tokens.clone().into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if !matches!(&token, TokenTree::Ident(ident) if ident == by_ident) {
+            continue;
+        }
+        if !matches!(tokens.next(), Some(TokenTree::Punct(punct)) if punct.as_char() == '=') {
+            continue;
+        }
+        let Some(TokenTree::Ident(method_name)) = tokens.next() else {
+            continue;
+        };
+        tokens.next(); // params group
+        if !matches!(tokens.next(), Some(TokenTree::Punct(punct)) if punct.as_char() == '-') {
+            return None;
+        }
+        tokens.next(); // '>' of '->'
+        let projection = tokens
+            .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+            .map(|token| token.to_string())
+            .collect::<String>();
+        return Some((method_name.to_string(), projection));
+    }
+    None
+}
+
+/// Parse `#[table(some_ident = some_method(...))]` by some_ident and return tokens inside of
+/// group of each method, example: `aggregate = revenue(sum(amount), group_by(user_id))`
+fn get_kind_method_groups(
+    meta: &MetaList,
+    by_ident: &str,
+) -> Vec<(String, proc_macro2::TokenStream)> {
+    let mut methods = vec![];
+    let mut tokens = meta.tokens.clone().into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if !matches!(&token, TokenTree::Ident(ident) if ident == by_ident) {
+            continue;
+        }
+        if !matches!(tokens.next(), Some(TokenTree::Punct(punct)) if punct.as_char() == '=') {
+            continue;
+        }
+        if let Some(TokenTree::Ident(method_name)) = tokens.next()
+            && let Some(TokenTree::Group(group)) = tokens.next()
+        {
+            methods.push((method_name.to_string(), group.stream()));
+        }
+    }
+    methods
+}
+
+/// Return `T` for type `Option<T>`
+pub fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Return `T` of array column `Vec<T>` or `Option<Vec<T>>`, bytes `Vec<u8>` and json fields are skipped
+pub fn array_element(field: &syn::Field) -> Option<&syn::Type> {
+    if is_json(field) {
+        return None;
+    }
+    let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+    if !matches!(column_type(ty).0, ColumnType::Array(_)) {
+        return None;
+    }
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    match &type_path.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parse `key = value, flag` pairs of group, example: `name = idx_x, columns = (a, b), unique`
+pub fn parse_key_values(tokens: proc_macro2::TokenStream) -> Vec<(String, Option<TokenTree>)> {
+    let mut pairs: Vec<(String, Option<TokenTree>)> = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let TokenTree::Ident(ident) = token else {
+            continue;
+        };
+        if matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '=') {
+            tokens.next();
+            pairs.push((ident.to_string(), tokens.next()));
+        } else {
+            pairs.push((ident.to_string(), None));
+        }
+    }
+    pairs
+}
+
+/// Value of `key = value` as string, quotes of literal are removed
+pub fn token_to_string(token: &TokenTree) -> String {
+    match token {
+        TokenTree::Literal(literal) => literal.to_string().replace('"', ""),
+        token => token.to_string(),
+    }
+}
+
+/// Idents of group `(a, b)`
+pub fn group_idents(token: &TokenTree) -> Vec<String> {
+    match token {
+        TokenTree::Group(group) => group
+            .stream()
+            .into_iter()
+            .filter(|token| matches!(token, TokenTree::Ident(_)))
+            .map(|token| token.to_string())
+            .collect(),
+        TokenTree::Ident(ident) => vec![ident.to_string()],
+        _ => vec![],
+    }
+}
+
+/// Parse `#[table(some_ident(...))]` by some_ident and return tokens inside of each group
+fn get_kind_groups(meta: &MetaList, by_ident: &str) -> Vec<proc_macro2::TokenStream> {
+    let mut groups = vec![];
+    let mut read_next_group = false;
+    for token in meta.tokens.clone() {
+        match token {
+            TokenTree::Ident(ident) => read_next_group = ident == by_ident,
+            TokenTree::Group(group) if read_next_group => {
+                groups.push(group.stream());
+                read_next_group = false;
+            }
+            _ => read_next_group = false,
+        }
+    }
+    groups
+}
+
+/// Index declared by field `#[table(index)]` or by table
+/// `#[table(index(name = idx_x, columns = (a, b), where = "deleted_at IS NULL", unique))]`
+pub struct Index {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    pub predicate: Option<String>,
+}
+
+impl Index {
+    /// Method name suffix, example: `a_and_b`
+    pub fn method_suffix(&self) -> String {
+        self.columns.join("_and_")
+    }
+
+    /// Fields of index columns in order of index
+    pub fn fields_with_type(&self, fields: &[(Ident, syn::Type)]) -> Vec<(Ident, syn::Type)> {
+        self.columns
+            .iter()
+            .map(|column| {
+                fields
+                    .iter()
+                    .find(|(name_field, _)| name_field.unraw() == column)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        panic!("Column {column} of index {} is not a field", self.name)
+                    })
+            })
+            .collect()
+    }
+
+    /// Condition of index columns with predicate of partial index
+    pub fn sql_condition(&self, filter_fields: &[String]) -> String {
+        let condition = generate_sql_params_condition(filter_fields);
+        match &self.predicate {
+            Some(predicate) => format!("{condition} AND ({predicate})"),
+            None => condition,
+        }
+    }
+}
+
+/// Tenant column `#[table(tenant = org_id)]`, generated methods take it as param and filter by it
+pub struct Tenant {
+    pub ident: Ident,
+    pub ty: syn::Type,
+    /// Qualified column of view, example: `orders.org_id`
+    pub column: String,
+    custom_type: bool,
+}
+
+impl Tenant {
+    /// Param of method, example: `org_id: i32`
+    pub fn param(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let ty = &self.ty;
+        quote! { #ident: #ty }
+    }
+
+    /// Bind of param, example: `org_id` or `org_id as _` for custom type
+    pub fn bind(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        if self.custom_type {
+            quote! { #ident as _ }
+        } else {
+            quote! { #ident }
+        }
+    }
+
+    /// Filter of tenant with param number, example:
+    /// "SELECT .. WHERE id=$1" -> "SELECT .. WHERE id=$1 AND org_id=$2".
+    ///
+    /// `has_where` is passed by caller, subquery of view can contain its own `WHERE`
+    pub fn scope_query(&self, query: &str, has_where: bool, index: usize) -> String {
+        let keyword = if has_where { "AND" } else { "WHERE" };
+        format!("{query} {keyword} {}=${index}", self.column)
+    }
+
+    /// Check of `insert(&self, conn, org_id)`: field of row must be the same tenant,
+    /// otherwise `sqlx::Error::InvalidArgument` is returned before query
+    pub fn check_field(&self, struct_name: &Ident) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let message = format!("{struct_name}.{ident} doesn't match tenant {ident}");
+        quote! {
+            if self.#ident != #ident {
+                return Err(sqlx::Error::InvalidArgument(#message.to_string()).into());
+            }
+        }
+    }
+
+    /// Params of method with tenant at the end, example: `id: i32, org_id: i32`
+    pub fn scope_params(&self, params: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let param = self.param();
+        if params.is_empty() {
+            param
+        } else {
+            quote! { #params, #param }
+        }
+    }
+}
+
+/// Connection of generated methods: pool for reads, connection for writes and transaction for
+/// row locking. `#[table(rls)]` requires `sql_macros::RlsTransaction` for all of them, so
+/// session variables of row level security can't be forgotten
+pub struct Executor {
+    pub rls: bool,
+}
+
+impl Executor {
+    /// Example: `pool: &sqlx::PgPool`
+    pub fn read_param(&self) -> proc_macro2::TokenStream {
+        if self.rls {
+            quote! { conn: &mut sql_macros::RlsTransaction }
+        } else {
+            quote! { pool: &sqlx::PgPool }
+        }
+    }
+
+    /// Example: `pool: &'a sqlx::PgPool` for stream which borrows params of method too
+    pub fn read_param_for(&self, lifetime: &syn::Lifetime) -> proc_macro2::TokenStream {
+        if self.rls {
+            quote! { conn: &#lifetime mut sql_macros::RlsTransaction }
+        } else {
+            quote! { pool: &#lifetime sqlx::PgPool }
+        }
+    }
+
+    /// Example: `pool`
+    pub fn read(&self) -> proc_macro2::TokenStream {
+        if self.rls {
+            quote! { &mut **conn }
+        } else {
+            quote! { pool }
+        }
+    }
+
+    /// Example: `conn: &mut sqlx::PgConnection`
+    pub fn write_param(&self) -> proc_macro2::TokenStream {
+        if self.rls {
+            quote! { conn: &mut sql_macros::RlsTransaction }
+        } else {
+            quote! { conn: &mut sqlx::PgConnection }
+        }
+    }
+
+    /// Example: `&mut *conn`
+    pub fn write(&self) -> proc_macro2::TokenStream {
+        if self.rls {
+            quote! { &mut **conn }
+        } else {
+            quote! { &mut *conn }
+        }
+    }
+
+    /// Example: `tx: &mut sqlx::Transaction<'_, sqlx::Postgres>`
+    pub fn lock_param(&self) -> proc_macro2::TokenStream {
+        if self.rls {
+            quote! { tx: &mut sql_macros::RlsTransaction }
+        } else {
+            quote! { tx: &mut sqlx::Transaction<'_, sqlx::Postgres> }
+        }
+    }
+}
+
+/// Query, params and binds of method filtered by tenant when `#[table(tenant = ..)]` is declared,
+/// `has_where` tells if query already ends by `WHERE` condition
+pub fn scope_tenant(
+    tenant: Option<&Tenant>,
+    query: &str,
+    has_where: bool,
+    params: &proc_macro2::TokenStream,
+    binds: &[proc_macro2::TokenStream],
+) -> (
+    String,
+    proc_macro2::TokenStream,
+    Vec<proc_macro2::TokenStream>,
+) {
+    let Some(tenant) = tenant else {
+        return (query.to_string(), params.clone(), binds.to_vec());
+    };
+    let mut binds = binds.to_vec();
+    binds.push(tenant.bind());
+    (
+        tenant.scope_query(query, has_where, binds.len()),
+        tenant.scope_params(params),
+        binds,
+    )
+}
+
+/// Method of `#[table(select = method(fields) -> Output)]` or `select_many`, output type derives
+/// `SqlTable` and its columns are selected at runtime. `select` returns `Option`, `select_many`
+/// returns `Vec`, `_for_update` variant is generated with `#[table(for_update)]`.
+///
+/// Return None for method without output type
+pub fn generate_projection_method(
+    kind: &str,
+    method_name: &str,
+    params: &proc_macro2::TokenStream,
+    sql_filters: &str,
+    filter_fields: &[Ident],
+    table: &Table,
+    tenant: Option<&Tenant>,
+) -> Option<proc_macro2::TokenStream> {
+    let projection = table.get_projection(kind, method_name)?;
+    let many = kind == "select_many";
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (read_param, read, lock_param) = (
+        executor.read_param(),
+        executor.read(),
+        executor.lock_param(),
+    );
+    let query_template = format!(
+        "SELECT {{}} FROM {} WHERE {sql_filters}",
+        table.get_from_clause()
+    );
+    let mut filter_fields = filter_fields.to_vec();
+    let (query_template, params) = match tenant {
+        Some(tenant) => {
+            filter_fields.push(tenant.ident.clone());
+            (
+                tenant.scope_query(&query_template, true, filter_fields.len()),
+                tenant.scope_params(params),
+            )
+        }
+        None => (query_template, params.clone()),
+    };
+    let mn = syn::parse_str::<Ident>(method_name).expect("Failed to parse code string");
+    let projection = syn::parse_str::<Type>(&projection).expect("Failed to parse input string");
+    let (output, fetch) = if many {
+        (quote! { Vec<#projection> }, quote! { fetch_all })
+    } else {
+        (quote! { Option<#projection> }, quote! { fetch_optional })
+    };
+    let doc = |query_template: &str| {
+        query_template.replace("{}", &format!("<{}>", projection.to_token_stream()))
+    };
+    // Example: emails_by_role_for_update(tx, role, limit), only in transaction
+    let locked_method = table.get_for_update().map(|for_update| {
+        let mn = syn::parse_str::<Ident>(&format!("{method_name}_for_update"))
+            .expect("Failed to parse code string");
+        let (query_template, limit_param, limit_bind) = if many {
+            (
+                format!(
+                    "{query_template} LIMIT ${} {for_update}",
+                    filter_fields.len() + 1
+                ),
+                quote! { limit: i64, },
+                quote! { .bind(limit) },
+            )
+        } else {
+            (
+                format!("{query_template} {for_update}"),
+                quote! {},
+                quote! {},
+            )
+        };
+        let doc = doc(&query_template);
+        quote! {
+            #[doc=#doc]
+            pub async fn #mn(#lock_param, #params, #limit_param) -> Result<#output, #error> {
+                let query = format!(
+                    #query_template,
+                    <#projection as sql_macros::SqlTable>::select_columns(),
+                );
+                let object = sqlx::query_as::<_, #projection>(&query)
+                    #(
+                        .bind(#filter_fields)
+                    )*
+                    #limit_bind
+                    .#fetch(&mut **tx)
+                    .await?;
+                Ok(object)
+            }
+        }
+    });
+    let doc = doc(&query_template);
+    Some(quote! {
+        #[doc=#doc]
+        pub async fn #mn(#read_param, #params) -> Result<#output, #error> {
+            let query = format!(
+                #query_template,
+                <#projection as sql_macros::SqlTable>::select_columns(),
+            );
+            let object = sqlx::query_as::<_, #projection>(&query)
+                #(
+                    .bind(#filter_fields)
+                )*
+                .#fetch(#read)
+                .await?;
+            Ok(object)
+        }
+
+        #locked_method
+    })
+}
+
+pub enum RelationKind {
+    BelongsTo,
+    HasMany,
+}
+
+/// Relation declared by `#[table(belongs_to = User, column = user_id, references = id)]`
+/// or `#[table(has_many = Post, column = user_id, references = id)]`
+pub struct Relation {
+    pub kind: RelationKind,
+    pub target: Ident,
+    /// Foreign key column
+    pub column: Option<String>,
+    /// Referenced column
+    pub references: Option<String>,
+    /// Plural of has_many target in method names
+    pub plural: Option<String>,
+}
+
+/// Columns of queue `#[table(queue(status = status, run_at = run_at, attempts = attempts, locked_at = locked_at))]`
+pub struct Queue {
+    pub status: String,
+    pub run_at: String,
+    pub attempts: String,
+    /// Time of claim, set by `claim_batch` and checked by `reap_stuck`
+    pub locked_at: String,
+}
+
 pub struct Table {
     struct_name: Ident,
     meta_list: Vec<MetaList>,
@@ -265,14 +1071,139 @@ impl Table {
         }
     }
 
-    /// Use for extract table name `#[table(name = users)]` else return struct name
+    /// Use for extract table name `#[table(name = users)]` or `#[table(from = orders)]` else return struct name
     pub fn get_name(&self) -> String {
         self.meta_list
             .iter()
             .find_map(|meta| get_kind_str(meta, "name"))
+            .or_else(|| self.get_from())
             .unwrap_or(format!("{}s", self.struct_name).to_lowercase())
     }
 
+    /// Use for extract main table of view `#[table(from = orders)]`
+    pub fn get_from(&self) -> Option<String> {
+        self.meta_list
+            .iter()
+            .find_map(|meta| get_kind_str(meta, "from"))
+    }
+
+    /// Use for extract joins of view `#[table(join(users, on = "users.id = orders.user_id"))]`,
+    /// `left_join(..)` is `LEFT JOIN`
+    pub fn get_joins(&self) -> Vec<String> {
+        let mut joins = vec![];
+        for meta in &self.meta_list {
+            for (kind, join) in [("join", "JOIN"), ("left_join", "LEFT JOIN")] {
+                for tokens in get_kind_groups(meta, kind) {
+                    let pairs = parse_key_values(tokens);
+                    let (table_name, _) = pairs
+                        .iter()
+                        .find(|(_, value)| value.is_none())
+                        .expect("Join must has table name");
+                    let on = pairs
+                        .iter()
+                        .find(|(name, _)| name == "on")
+                        .and_then(|(_, value)| value.as_ref())
+                        .map(token_to_string)
+                        .expect("Join must has on = \"condition\"");
+                    joins.push(format!("{join} {table_name} ON {on}"));
+                }
+            }
+        }
+        joins
+    }
+
+    /// Table name with joins for `FROM`, example: `orders JOIN users ON users.id = orders.user_id`
+    pub fn get_from_clause(&self) -> String {
+        let mut from = vec![self.get_name()];
+        from.extend(self.get_joins());
+        from.join(" ")
+    }
+
+    /// Use for extract error of generated methods `#[table(error = sql_macros::Error)]`,
+    /// default `sqlx::Error`
+    pub fn get_error(&self) -> proc_macro2::TokenStream {
+        let error = self
+            .meta_list
+            .iter()
+            .find_map(|meta| get_kind_path(meta, "error"))
+            .unwrap_or("sqlx::Error".to_string());
+        syn::parse_str::<proc_macro2::TokenStream>(&error).expect("Failed to parse error type")
+    }
+
+    /// Use for extract row locking `#[table(for_update)]`, `#[table(for_update = skip_locked)]`
+    /// or `#[table(for_update = nowait)]`, return Some("FOR UPDATE SKIP LOCKED")
+    pub fn get_for_update(&self) -> Option<String> {
+        if self
+            .meta_list
+            .iter()
+            .any(|meta| meta_has_flag(meta, "for_update"))
+        {
+            return Some("FOR UPDATE".to_string());
+        }
+        let option = self
+            .meta_list
+            .iter()
+            .find_map(|meta| get_kind_path(meta, "for_update"))?;
+        match option.as_str() {
+            "skip_locked" => Some("FOR UPDATE SKIP LOCKED".to_string()),
+            "nowait" => Some("FOR UPDATE NOWAIT".to_string()),
+            _ => panic!("Unknown for_update = {option}, expected skip_locked or nowait"),
+        }
+    }
+
+    /// Use for `#[table(required)]`, generates `select_one_by_*`, `delete_one_by_*` and `update_one`
+    /// next to methods which return `Option`
+    pub fn has_required(&self) -> bool {
+        self.meta_list
+            .iter()
+            .any(|meta| meta_has_flag(meta, "required"))
+    }
+
+    /// Qualified column of field of view: `#[table(source = users.email)]` or `orders.field`.
+    ///
+    /// Return None for struct without `#[table(from = ..)]`
+    pub fn get_column_source(&self, field: &syn::Field) -> Option<String> {
+        let from = self.get_from()?;
+        get_field_kind_path(field, "source")
+            .or_else(|| Some(format!("{from}.{}", field.ident.as_ref()?.unraw())))
+    }
+
+    /// Unqualified column of field for `SET` of update, `#[table(source = orders.total)]` is `total`
+    pub fn get_column_name(&self, field: &syn::Field) -> Option<String> {
+        let ident = field.ident.as_ref()?;
+        let column = self
+            .get_column_source(field)
+            .unwrap_or(ident.unraw().to_string());
+        Some(column.rsplit('.').next().unwrap_or(&column).to_string())
+    }
+
+    /// Column of field in queries, qualified for view, example: `r#type` is `type`
+    pub fn get_column(&self, field: &syn::Field) -> Option<String> {
+        let ident = field.ident.as_ref()?;
+        Some(
+            self.get_column_source(field)
+                .unwrap_or(ident.unraw().to_string()),
+        )
+    }
+
+    /// Qualified columns of fields for `WHERE` of view
+    pub fn qualify_columns(
+        &self,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+        names: &[Ident],
+    ) -> Vec<String> {
+        names
+            .iter()
+            .map(|name| {
+                fields
+                    .iter()
+                    .find(|field| field.ident.as_ref() == Some(name))
+                    .and_then(|field| self.get_column(field))
+                    .unwrap_or(name.unraw().to_string())
+            })
+            .collect()
+    }
+
     /// Use for extract return special columns for update `#[table(spec_columns = "updated_at=NOW()")]`
     pub fn get_spec_columns(&self) -> Option<String> {
         self.meta_list
@@ -294,6 +1225,145 @@ impl Table {
             .find_map(|meta| get_kind_str(meta, "return_fields"))
     }
 
+    /// Columns of `RETURNING`: `return_fields`, columns of struct with type overrides for
+    /// `#[table(return_type = Self)]` or struct with json fields, otherwise `*`
+    pub fn get_returning(
+        &self,
+        struct_name: &Ident,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ) -> String {
+        let sql_columns = get_sql_columns(self, fields);
+        if let Some(return_fields) = self.get_return_fields() {
+            // Example: "id, settings AS \"settings!: sqlx::types::Json<Settings>\""
+            return return_fields
+                .split(',')
+                .map(|name| {
+                    let name = name.trim();
+                    fields
+                        .iter()
+                        .position(|field| {
+                            is_json(field)
+                                && field
+                                    .ident
+                                    .as_ref()
+                                    .is_some_and(|ident| ident.unraw() == name)
+                        })
+                        .map(|index| sql_columns[index].clone())
+                        .unwrap_or(name.to_string())
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+        }
+        match self.get_return_type() {
+            Some(return_type) if return_type == "Self" || *struct_name == return_type => {
+                sql_columns.join(", ")
+            }
+            _ if fields.iter().any(is_json) => sql_columns.join(", "),
+            _ => "*".to_string(),
+        }
+    }
+
+    /// Use for extract indexes of fields `#[table(index)]` and of table
+    /// `#[table(index(name = idx_x, columns = (a, b), where = "deleted_at IS NULL"))]`
+    pub fn get_indexes(
+        &self,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ) -> Vec<Index> {
+        let table_name = self.get_name();
+        let mut indexes = fields
+            .iter()
+            .filter(|field| field_has_flag(field, "index"))
+            .filter_map(|field| Some(field.ident.as_ref()?.unraw()))
+            .map(|ident| Index {
+                name: format!("idx_{table_name}_{ident}"),
+                columns: vec![ident.to_string()],
+                unique: false,
+                predicate: None,
+            })
+            .collect::<Vec<_>>();
+
+        for tokens in self
+            .meta_list
+            .iter()
+            .flat_map(|meta| get_kind_groups(meta, "index"))
+        {
+            let pairs = parse_key_values(tokens);
+            let value = |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| name == key)
+                    .and_then(|(_, value)| value.as_ref())
+            };
+            let columns = value("columns")
+                .map(group_idents)
+                .expect("Index must has columns = (column1, column2)");
+            let name = value("name")
+                .map(token_to_string)
+                .unwrap_or(format!("idx_{table_name}_{}", columns.join("_")));
+            indexes.push(Index {
+                name,
+                columns,
+                unique: pairs
+                    .iter()
+                    .any(|(name, value)| name == "unique" && value.is_none()),
+                predicate: value("where").map(token_to_string),
+            });
+        }
+        indexes
+    }
+
+    /// Indexes which get own `select_by_*` if unique or `select_many_by_*` methods. Index of one
+    /// column with method of field and index of same columns as previous one are skipped, so
+    /// names don't collide; full index is preferred over partial one
+    pub fn get_method_indexes(
+        &self,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+        unique: bool,
+        field_filters: &[Ident],
+    ) -> Vec<Index> {
+        let mut indexes = self
+            .get_indexes(fields)
+            .into_iter()
+            .filter(|index| index.unique == unique)
+            .collect::<Vec<_>>();
+        indexes.sort_by_key(|index| index.predicate.is_some());
+        let mut suffixes = field_filters
+            .iter()
+            .map(|field| field.unraw().to_string())
+            .collect::<Vec<_>>();
+        indexes.retain(|index| {
+            let suffix = index.method_suffix();
+            let generated = suffixes.contains(&suffix);
+            suffixes.push(suffix);
+            !generated
+        });
+        indexes
+    }
+
+    /// Use for extract columns of queue
+    /// `#[table(queue(status = status, run_at = run_at, attempts = attempts, locked_at = locked_at))]`,
+    /// missing keys are columns with same name
+    pub fn get_queue(&self) -> Option<Queue> {
+        let tokens = self
+            .meta_list
+            .iter()
+            .flat_map(|meta| get_kind_groups(meta, "queue"))
+            .next()?;
+        let pairs = parse_key_values(tokens);
+        let value = |key: &str| {
+            pairs
+                .iter()
+                .find(|(name, _)| name == key)
+                .and_then(|(_, value)| value.as_ref())
+                .map(token_to_string)
+        };
+        Some(Queue {
+            status: value("status").unwrap_or("status".to_string()),
+            run_at: value("run_at").unwrap_or("run_at".to_string()),
+            attempts: value("attempts").unwrap_or("attempts".to_string()),
+            locked_at: value("locked_at").unwrap_or("locked_at".to_string()),
+        })
+    }
 
     /// Use for extract methods select `#[table(select = get_active_user(is_active, is_removed))]`
     pub fn get_select(&self) -> Vec<(String, Vec<String>)> {
@@ -303,6 +1373,60 @@ impl Table {
             .collect()
     }
 
+    /// Use for extract methods of full-text search `#[table(search = search_posts(title, body))]`
+    pub fn get_search(&self) -> Vec<(String, Vec<String>)> {
+        self.meta_list
+            .iter()
+            .filter_map(|meta| get_kind_method_with_params(meta, "search"))
+            .collect()
+    }
+
+    /// Use for extract config of full-text search `#[table(language = english)]`
+    pub fn get_language(&self) -> Option<String> {
+        self.meta_list
+            .iter()
+            .find_map(|meta| get_kind_str(meta, "language"))
+    }
+
+    /// Check `#[table(dialect = sqlite)]`, default dialect of generated queries is postgres
+    pub fn is_sqlite(&self) -> bool {
+        self.meta_list
+            .iter()
+            .find_map(|meta| get_kind_str(meta, "dialect"))
+            .is_some_and(|dialect| dialect == "sqlite")
+    }
+
+    /// Use for extract connection of generated methods, `#[table(rls)]` requires `sql_macros::RlsTransaction`
+    pub fn get_executor(&self) -> Executor {
+        Executor {
+            rls: self.meta_list.iter().any(|meta| meta_has_flag(meta, "rls")),
+        }
+    }
+
+    /// Use for extract tenant column `#[table(tenant = org_id)]`, it must be required field of struct
+    pub fn get_tenant(
+        &self,
+        fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    ) -> Option<Tenant> {
+        let name = self
+            .meta_list
+            .iter()
+            .find_map(|meta| get_kind_str(meta, "tenant"))?;
+        let field = fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| *ident == name))
+            .unwrap_or_else(|| panic!("Tenant {name} must be field of {}", self.struct_name));
+        if option_inner(&field.ty).is_some() {
+            panic!("Tenant {name} of {} can't be Option", self.struct_name);
+        }
+        Some(Tenant {
+            ident: field.ident.clone()?,
+            ty: field.ty.clone(),
+            column: self.get_column_source(field).unwrap_or(name),
+            custom_type: is_custom_type(field),
+        })
+    }
+
     /// Use for extract methods select_many `#[table(select_many = get_user_by_removed(is_active, is_removed))]`
     pub fn get_select_many(&self) -> Vec<(String, Vec<String>)> {
         self.meta_list
@@ -311,6 +1435,25 @@ impl Table {
             .collect()
     }
 
+    /// Use for extract output type of method `#[table(select_many = emails_by_role(role) -> UserEmail)]`
+    /// by kind `select_many` and method name
+    pub fn get_projection(&self, kind: &str, method_name: &str) -> Option<String> {
+        self.meta_list
+            .iter()
+            .filter_map(|meta| get_kind_method_projection(meta, kind))
+            .find(|(name, _)| name == method_name)
+            .map(|(_, projection)| projection)
+    }
+
+    /// Use for extract aggregate methods
+    /// `#[table(aggregate = revenue_by_day(sum(amount), group_by(date_trunc_day(created_at))))]`
+    pub fn get_aggregates(&self) -> Vec<(String, proc_macro2::TokenStream)> {
+        self.meta_list
+            .iter()
+            .flat_map(|meta| get_kind_method_groups(meta, "aggregate"))
+            .collect()
+    }
+
     /// Use for extract methods delete `#[table(delete = delete_by_user(id, user_id))]`
     pub fn get_delete(&self) -> Vec<(String, Vec<String>)> {
         self.meta_list
@@ -319,6 +1462,36 @@ impl Table {
             .collect()
     }
 
+    /// Use for extract relations `#[table(belongs_to = User, column = user_id)]`,
+    /// `#[table(has_many = Post)]` or `#[table(has_many = Person, plural = people)]`
+    pub fn get_relations(&self) -> Vec<Relation> {
+        self.meta_list
+            .iter()
+            .filter_map(|meta| {
+                let pairs = parse_key_values(meta.tokens.clone());
+                let value = |key: &str| {
+                    pairs
+                        .iter()
+                        .find(|(name, _)| name == key)
+                        .and_then(|(_, value)| value.as_ref())
+                        .map(token_to_string)
+                };
+                let (kind, target) = if let Some(target) = value("belongs_to") {
+                    (RelationKind::BelongsTo, target)
+                } else {
+                    (RelationKind::HasMany, value("has_many")?)
+                };
+                Some(Relation {
+                    kind,
+                    target: syn::parse_str(&target).expect("Failed to parse relation type"),
+                    column: value("column"),
+                    references: value("references"),
+                    plural: value("plural"),
+                })
+            })
+            .collect()
+    }
+
     // /// Use for extract methods delete `#[table(update = update_by_user(id, user_id))]`
     // pub fn get_update(&self) -> Vec<(String, Vec<String>)> {
     //     self.meta_list
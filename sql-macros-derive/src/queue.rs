@@ -0,0 +1,211 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+
+use crate::insert::generate_insert_query;
+use crate::parser::{
+    RowType, Table, fields_named_struct, generate_sql_params_condition, get_filters,
+    get_ident_bind, get_sql_columns, parse_fields_with_type, scope_tenant,
+};
+
+pub fn sql_queue_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (write_param, write) = (executor.write_param(), executor.write());
+    let struct_name = input.ident.clone();
+    let table_name = table.get_name();
+    let queue = table
+        .get_queue()
+        .unwrap_or_else(|| panic!("{struct_name} must has #[table(queue(status = status, run_at = run_at, attempts = attempts))]"));
+
+    let fields = fields_named_struct(input);
+    let sql_columns = get_sql_columns(&table, fields).join(", ");
+
+    let primary_key = get_filters(parse_fields_with_type(fields, "primary_key"));
+    if primary_key.is_empty() {
+        panic!("{struct_name} must has field with #[table(primary_key)]");
+    }
+    let pk_columns = primary_key
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let status = &queue.status;
+    let run_at = &queue.run_at;
+    let attempts = &queue.attempts;
+    let locked_at = &queue.locked_at;
+    let attempts_type = fields
+        .iter()
+        .find(|field| field.ident.as_ref().is_some_and(|ident| ident == attempts))
+        .map(|field| field.ty.clone())
+        .unwrap_or_else(|| panic!("Field {attempts} not contains in {struct_name}"));
+
+    // Primary key, status, attempts and time of claim are set by database
+    let idents = fields
+        .iter()
+        .filter_map(|field| field.ident.clone())
+        .filter(|ident| {
+            !primary_key.contains(ident)
+                && ident != status
+                && ident != attempts
+                && ident != locked_at
+        })
+        .collect::<Vec<_>>();
+    let mut insert_columns = idents
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect::<Vec<String>>();
+    insert_columns.extend([status.clone(), attempts.clone()]);
+    let enqueue_query = format!(
+        "{} RETURNING {sql_columns}",
+        generate_insert_query(
+            &table_name,
+            &insert_columns,
+            &["'queued'".to_string(), "0".to_string()]
+        )
+    );
+
+    // Example: enqueue(&self, conn, org_id) and claim_batch(conn, limit, org_id) for
+    // `#[table(tenant = org_id)]`, workers claim jobs of one tenant
+    let tenant = table.get_tenant(fields);
+    let tenant_params = tenant.as_ref().map(|tenant| tenant.param());
+    let tenant_check = tenant
+        .as_ref()
+        .map(|tenant| tenant.check_field(&struct_name));
+    let (claim_filter, claim_binds) = match &tenant {
+        Some(tenant) => (
+            format!(" AND {}=$2", tenant.column),
+            vec![quote! { limit }, tenant.bind()],
+        ),
+        None => (String::new(), vec![quote! { limit }]),
+    };
+    let claim_query = format!(
+        "UPDATE {table_name} SET {status}='running', {attempts}={attempts}+1, {locked_at}=now() \
+        WHERE ({pk_columns}) IN (SELECT {pk_columns} FROM {table_name} \
+        WHERE {status}='queued' AND {run_at}<=now(){claim_filter} ORDER BY {run_at} LIMIT $1 FOR UPDATE SKIP LOCKED) \
+        RETURNING {sql_columns}"
+    );
+
+    let primary_key_binds = primary_key
+        .iter()
+        .map(|ident| quote! { self.#ident as _ })
+        .collect::<Vec<_>>();
+    let sql_filters = generate_sql_params_condition(&table.qualify_columns(fields, &primary_key));
+    let complete_query = format!("UPDATE {table_name} SET {status}='done' WHERE {sql_filters}");
+    let (complete_query, _, complete_binds) = scope_tenant(
+        tenant.as_ref(),
+        &complete_query,
+        true,
+        &quote! {},
+        &primary_key_binds,
+    );
+
+    // $1 is backoff, $2 is max attempts
+    let pk_offset = primary_key
+        .iter()
+        .enumerate()
+        .map(|(index, column)| format!("{column}=${}", index + 3))
+        .collect::<Vec<String>>()
+        .join(" AND ");
+    let fail_query = format!(
+        "UPDATE {table_name} SET {status}=CASE WHEN {attempts}>=$2 THEN 'failed' ELSE 'queued' END, \
+        {run_at}=now() + make_interval(secs => $1) WHERE {pk_offset}"
+    );
+    let (fail_query, _, fail_binds) = scope_tenant(
+        tenant.as_ref(),
+        &fail_query,
+        true,
+        &quote! {},
+        &[
+            vec![
+                quote! { backoff.as_secs_f64() },
+                quote! { max_attempts as _ },
+            ],
+            primary_key_binds,
+        ]
+        .concat(),
+    );
+
+    // $1 is timeout, $2 is max attempts
+    let reap_query = format!(
+        "UPDATE {table_name} SET {status}=CASE WHEN {attempts}>=$2 THEN 'failed' ELSE 'queued' END \
+        WHERE {status}='running' AND {locked_at}<now() - make_interval(secs => $1)"
+    );
+    let (reap_query, _, reap_binds) = scope_tenant(
+        tenant.as_ref(),
+        &reap_query,
+        true,
+        &quote! {},
+        &[
+            quote! { timeout.as_secs_f64() },
+            quote! { max_attempts as _ },
+        ],
+    );
+
+    // Example: self.email as _, sqlx::types::Json(&self.payload) as _
+    let enqueue_binds = idents
+        .iter()
+        .map(|ident| get_ident_bind(fields, ident))
+        .collect::<Vec<_>>();
+    let row_type = RowType::new(&struct_name, fields);
+    let enqueue = row_type.query_as(&enqueue_query, quote! { #(#enqueue_binds),* });
+    let claim = row_type.query_as(&claim_query, quote! { #(#claim_binds),* });
+
+    let token_stream = quote! {
+        impl #struct_name {
+            #[doc=#enqueue_query]
+            pub async fn enqueue(&self, #write_param, #tenant_params) -> Result<#struct_name, #error> {
+                #tenant_check
+                let object = #enqueue.fetch_one(#write).await?;
+                Ok(object)
+            }
+
+            #[doc=#claim_query]
+            pub async fn claim_batch(#write_param, limit: i64, #tenant_params) -> Result<Vec<#struct_name>, #error> {
+                let object = #claim.fetch_all(#write).await?;
+                Ok(object)
+            }
+
+            #[doc=#complete_query]
+            pub async fn complete(&self, #write_param, #tenant_params) -> Result<sqlx::any::AnyQueryResult, #error> {
+                let result = sqlx::query!(#complete_query, #(#complete_binds),*)
+                    .execute(#write)
+                    .await?;
+                Ok(result.into())
+            }
+
+            /// Return job to queue after backoff, job is failed when attempts reach max_attempts
+            #[doc=#fail_query]
+            pub async fn fail_with_retry(
+                &self,
+                #write_param,
+                backoff: std::time::Duration,
+                max_attempts: #attempts_type,
+                #tenant_params
+            ) -> Result<sqlx::any::AnyQueryResult, #error> {
+                let result = sqlx::query!(#fail_query, #(#fail_binds),*)
+                .execute(#write)
+                .await?;
+                Ok(result.into())
+            }
+
+            /// Return to queue running jobs claimed earlier than timeout, job is failed when
+            /// attempts reach max_attempts
+            #[doc=#reap_query]
+            pub async fn reap_stuck(
+                #write_param,
+                timeout: std::time::Duration,
+                max_attempts: #attempts_type,
+                #tenant_params
+            ) -> Result<sqlx::any::AnyQueryResult, #error> {
+                let result = sqlx::query!(#reap_query, #(#reap_binds),*)
+                    .execute(#write)
+                    .await?;
+                Ok(result.into())
+            }
+        }
+    };
+    token_stream.into()
+}
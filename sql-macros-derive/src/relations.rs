@@ -0,0 +1,229 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+use crate::parser::{
+    RelationKind, Table, extract_fields_as_params, fields_named_struct, option_inner,
+    parse_fields_with_type, pluralize, to_snake_case,
+};
+
+pub fn sql_relations_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (read_param, read) = (executor.read_param(), executor.read());
+    let struct_name = input.ident.clone();
+
+    let fields = fields_named_struct(input);
+    let ff = extract_fields_as_params(fields);
+    let field_type = |name: &str| {
+        ff.iter()
+            .find(|(name_field, _)| name_field == name)
+            .map(|(_, ty)| ty.clone())
+            .unwrap_or_else(|| panic!("Field {name} not contains in {struct_name}"))
+    };
+    let primary_key = parse_fields_with_type(fields, "primary_key")
+        .first()
+        .map(|(name, _)| name.unraw().to_string())
+        .unwrap_or("id".to_string());
+
+    // Example: load_posts(&self, pool, org_id) for `#[table(tenant = org_id)]`, target is filtered
+    // by its own tenant column or by column with the same name
+    let tenant = table.get_tenant(fields);
+    let tenant_params = tenant.as_ref().map(|tenant| tenant.param());
+    let tenant_bind = tenant.as_ref().map(|tenant| {
+        let ident = &tenant.ident;
+        quote! { .bind(#ident) }
+    });
+
+    let mut methods = vec![];
+    let mut method_names = vec![];
+    for relation in table.get_relations() {
+        let target = relation.target;
+        let target_name = to_snake_case(&target.to_string());
+        let requirement = format!(
+            "\n`{target}` must derive `SqlTable` and `sqlx::FromRow`, its columns are selected at runtime"
+        );
+        let tenant_filter = match &tenant {
+            Some(tenant) => {
+                let ident = tenant.ident.unraw().to_string();
+                quote! {
+                    format!(" AND {}=$2", <#target as sql_macros::SqlTable>::tenant().unwrap_or(#ident))
+                }
+            }
+            None => quote! { String::new() },
+        };
+
+        match relation.kind {
+            RelationKind::BelongsTo => {
+                let column = relation.column.unwrap_or(format!("{target_name}_id"));
+                let references = relation.references.unwrap_or("id".to_string());
+                let column_ident = format_ident!("{column}");
+                let references_ident = format_ident!("{references}");
+                let column_type = field_type(&column);
+                // Nullable foreign key is skipped in batch
+                let (key_type, keys) = match option_inner(&column_type) {
+                    Some(inner) => (
+                        inner.clone(),
+                        quote! { rows.iter().filter_map(|row| row.#column_ident.clone()).collect::<Vec<_>>() },
+                    ),
+                    None => (
+                        column_type.clone(),
+                        quote! { rows.iter().map(|row| row.#column_ident.clone()).collect::<Vec<_>>() },
+                    ),
+                };
+
+                // Example: load_author for `column = author_id`, load_user for default `user_id`
+                let name = column.strip_suffix("_id").unwrap_or(&column);
+                let load = format_ident!("load_{name}");
+                let load_for = format_ident!("load_{name}_for");
+                method_names.push(load.to_string());
+                let doc = format!("SELECT .. FROM <{target}> WHERE {references}=self.{column}");
+                let doc_for = format!("SELECT .. FROM <{target}> WHERE {references} = ANY($1)");
+                methods.push(quote! {
+                    #[doc=#doc]
+                    #[doc=#requirement]
+                    pub async fn #load(&self, #read_param, #tenant_params) -> Result<Option<#target>, #error> {
+                        let query = format!(
+                            "SELECT {} FROM {} WHERE {}=$1{}",
+                            <#target as sql_macros::SqlTable>::select_columns(),
+                            <#target as sql_macros::SqlTable>::name(),
+                            #references,
+                            #tenant_filter,
+                        );
+                        let object = sqlx::query_as::<_, #target>(&query)
+                            .bind(&self.#column_ident)
+                            #tenant_bind
+                            .fetch_optional(#read)
+                            .await?;
+                        Ok(object)
+                    }
+
+                    #[doc=#doc_for]
+                    #[doc=#requirement]
+                    pub async fn #load_for(
+                        #read_param,
+                        rows: &[#struct_name],
+                        #tenant_params
+                    ) -> Result<std::collections::HashMap<#key_type, #target>, #error> {
+                        let keys = #keys;
+                        let query = format!(
+                            "SELECT {} FROM {} WHERE {} = ANY($1){}",
+                            <#target as sql_macros::SqlTable>::select_columns(),
+                            <#target as sql_macros::SqlTable>::name(),
+                            #references,
+                            #tenant_filter,
+                        );
+                        let objects = sqlx::query_as::<_, #target>(&query)
+                            .bind(keys)
+                            #tenant_bind
+                            .fetch_all(#read)
+                            .await?;
+                        Ok(objects
+                            .into_iter()
+                            .filter_map(|object| {
+                                let key = sql_macros::ForeignKey::<#key_type>::key(
+                                    object.#references_ident.clone(),
+                                );
+                                Some((key?, object))
+                            })
+                            .collect())
+                    }
+                });
+            }
+            RelationKind::HasMany => {
+                let column = relation
+                    .column
+                    .unwrap_or(format!("{}_id", to_snake_case(&struct_name.to_string())));
+                let references = relation.references.unwrap_or(primary_key.clone());
+                let column_ident = format_ident!("{column}");
+                let references_ident = format_ident!("{references}");
+                let key_type = field_type(&references);
+
+                let plural = relation.plural.unwrap_or(pluralize(&target_name));
+                let load = format_ident!("load_{plural}");
+                let load_for = format_ident!("load_{plural}_for");
+                method_names.push(load.to_string());
+                let doc = format!("SELECT .. FROM <{target}> WHERE {column}=self.{references}");
+                let doc_for = format!("SELECT .. FROM <{target}> WHERE {column} = ANY($1)");
+                methods.push(quote! {
+                    #[doc=#doc]
+                    #[doc=#requirement]
+                    pub async fn #load(&self, #read_param, #tenant_params) -> Result<Vec<#target>, #error> {
+                        let query = format!(
+                            "SELECT {} FROM {} WHERE {}=$1{}",
+                            <#target as sql_macros::SqlTable>::select_columns(),
+                            <#target as sql_macros::SqlTable>::name(),
+                            #column,
+                            #tenant_filter,
+                        );
+                        let object = sqlx::query_as::<_, #target>(&query)
+                            .bind(&self.#references_ident)
+                            #tenant_bind
+                            .fetch_all(#read)
+                            .await?;
+                        Ok(object)
+                    }
+
+                    #[doc=#doc_for]
+                    #[doc=#requirement]
+                    pub async fn #load_for(
+                        #read_param,
+                        rows: &[#struct_name],
+                        #tenant_params
+                    ) -> Result<std::collections::HashMap<#key_type, Vec<#target>>, #error> {
+                        let keys = rows
+                            .iter()
+                            .map(|row| row.#references_ident.clone())
+                            .collect::<Vec<_>>();
+                        let query = format!(
+                            "SELECT {} FROM {} WHERE {} = ANY($1){}",
+                            <#target as sql_macros::SqlTable>::select_columns(),
+                            <#target as sql_macros::SqlTable>::name(),
+                            #column,
+                            #tenant_filter,
+                        );
+                        let objects = sqlx::query_as::<_, #target>(&query)
+                            .bind(keys)
+                            #tenant_bind
+                            .fetch_all(#read)
+                            .await?;
+                        let mut grouped: std::collections::HashMap<#key_type, Vec<#target>> =
+                            std::collections::HashMap::new();
+                        // Example: post_id of `Option<i32>` is grouped by `i32`
+                        for object in objects {
+                            let key = sql_macros::ForeignKey::<#key_type>::key(
+                                object.#column_ident.clone(),
+                            );
+                            if let Some(key) = key {
+                                grouped.entry(key).or_default().push(object);
+                            }
+                        }
+                        Ok(grouped)
+                    }
+                });
+            }
+        }
+    }
+
+    // Example: belongs_to = User twice without `column`, has_many = Post twice without `plural`
+    if let Some(name) = method_names
+        .iter()
+        .enumerate()
+        .find_map(|(index, name)| method_names[..index].contains(name).then_some(name))
+    {
+        let message = format!(
+            "Relations of {struct_name} generate {name} twice, set `column` of belongs_to or `plural` of has_many"
+        );
+        return syn::Error::new_spanned(&struct_name, message)
+            .to_compile_error()
+            .into();
+    }
+
+    let token_stream = quote! {
+        impl #struct_name {
+            #(#methods)*
+        }
+    };
+    token_stream.into()
+}
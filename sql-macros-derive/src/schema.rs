@@ -0,0 +1,228 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{GenericArgument, PathArguments, Type, ext::IdentExt};
+
+use crate::parser::{
+    Table, field_has_flag, fields_named_struct, get_field_kind_str, get_sql_type, is_json,
+    to_snake_case,
+};
+
+/// Mirror of `sql_macros::SqlType` known at compile time of macros
+pub enum ColumnType {
+    /// Name of variant `sql_macros::SqlType` without data
+    Named(&'static str),
+    Array(Box<ColumnType>),
+    Custom(String),
+}
+
+impl ColumnType {
+    fn to_tokens(&self) -> proc_macro2::TokenStream {
+        match self {
+            ColumnType::Named(name) => {
+                let variant = format_ident!("{name}");
+                quote! { sql_macros::SqlType::#variant }
+            }
+            ColumnType::Array(inner) => {
+                let inner = inner.to_tokens();
+                quote! { sql_macros::SqlType::Array(Box::new(#inner)) }
+            }
+            ColumnType::Custom(name) => quote! { sql_macros::SqlType::Custom(#name.to_string()) },
+        }
+    }
+}
+
+fn generic_argument(segment: &syn::PathSegment) -> Option<&Type> {
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Type isn't mapped to sql type, example: `u64`, `char` or enum `Role`
+fn is_unknown(column_type: &ColumnType) -> bool {
+    match column_type {
+        ColumnType::Named(_) => false,
+        ColumnType::Array(inner) => is_unknown(inner),
+        ColumnType::Custom(_) => true,
+    }
+}
+
+/// Map rust type of field to column type, return (type, nullable)
+pub fn column_type(ty: &Type) -> (ColumnType, bool) {
+    let segment = match ty {
+        Type::Reference(reference) => return column_type(&reference.elem),
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .expect("Expect type with name"),
+        _ => panic!("Unexpected type"),
+    };
+
+    let name = segment.ident.to_string();
+    let inner = generic_argument(segment);
+    let column_type = match (name.as_str(), inner) {
+        ("Option", Some(inner)) => return (column_type(inner).0, true),
+        ("Vec", Some(Type::Path(inner))) if inner.path.is_ident("u8") => ColumnType::Named("Bytes"),
+        ("Vec", Some(inner)) => ColumnType::Array(Box::new(column_type(inner).0)),
+        ("i8" | "i16", _) => ColumnType::Named("SmallInt"),
+        ("i32" | "u16", _) => ColumnType::Named("Integer"),
+        ("i64" | "u32", _) => ColumnType::Named("BigInt"),
+        ("f32", _) => ColumnType::Named("Real"),
+        ("f64", _) => ColumnType::Named("Double"),
+        ("Decimal" | "BigDecimal", _) => ColumnType::Named("Numeric"),
+        ("bool", _) => ColumnType::Named("Boolean"),
+        ("String" | "str", _) => ColumnType::Named("Text"),
+        ("Uuid", _) => ColumnType::Named("Uuid"),
+        ("NaiveDate" | "Date", _) => ColumnType::Named("Date"),
+        ("NaiveTime" | "Time", _) => ColumnType::Named("Time"),
+        ("NaiveDateTime" | "PrimitiveDateTime", _) => ColumnType::Named("Timestamp"),
+        ("DateTime" | "OffsetDateTime", _) => ColumnType::Named("TimestampTz"),
+        ("Value" | "JsonValue" | "Json", _) => ColumnType::Named("Json"),
+        _ => ColumnType::Custom(to_snake_case(&name)),
+    };
+    (column_type, false)
+}
+
+/// Column type of field, name of `#[table(sql_type = "user_role")]` replaces type of rust,
+/// return (type, nullable)
+pub fn field_column_type(field: &syn::Field) -> (ColumnType, bool) {
+    let (column_type, nullable) = column_type(&field.ty);
+    let Some(name) = get_sql_type(field) else {
+        return (column_type, nullable);
+    };
+    // Example: `Vec<Role>` of `sql_type = "user_role"` is `user_role[]`
+    let column_type = match column_type {
+        ColumnType::Array(_) => ColumnType::Array(Box::new(ColumnType::Custom(name))),
+        _ => ColumnType::Custom(name),
+    };
+    (column_type, nullable)
+}
+
+/// Postgres type name of field for casts, example: `$1::int4[]`, `$1::user_role[]`
+/// for `#[table(sql_type = "user_role")]`.
+/// None for arrays and custom types without name, their name in database is unknown
+pub fn pg_type_name(field: &syn::Field) -> Option<String> {
+    let name = match field_column_type(field).0 {
+        ColumnType::Named(name) => match name {
+            "SmallInt" => "int2",
+            "Integer" => "int4",
+            "BigInt" => "int8",
+            "Real" => "float4",
+            "Double" => "float8",
+            "Numeric" => "numeric",
+            "Boolean" => "bool",
+            "Text" => "text",
+            "Bytes" => "bytea",
+            "Uuid" => "uuid",
+            "Date" => "date",
+            "Time" => "time",
+            "Timestamp" => "timestamp",
+            "TimestampTz" => "timestamptz",
+            "Json" => "jsonb",
+            name => panic!("Unexpected column type {name}"),
+        }
+        .to_string(),
+        ColumnType::Custom(name) if get_sql_type(field).is_some() => name,
+        ColumnType::Custom(_) | ColumnType::Array(_) => return None,
+    };
+    Some(name)
+}
+
+pub fn sql_schema_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let struct_name = input.ident.clone();
+    let table_name = table.get_name();
+
+    let fields = fields_named_struct(input);
+
+    let mut columns = vec![];
+    for field in fields {
+        let name = field
+            .ident
+            .as_ref()
+            .expect("Struct must by named type struct")
+            .unraw()
+            .to_string();
+        let (sql_type, nullable) = field_column_type(field);
+        // Enum or other custom type must be declared, `u64` or `char` can't be stored as is
+        if is_unknown(&sql_type)
+            && !is_json(field)
+            && !field_has_flag(field, "sql_type")
+            && get_sql_type(field).is_none()
+            && get_field_kind_str(field, "as_type").is_none()
+        {
+            let message = format!(
+                "Unknown sql type of field {name}, use #[table(sql_type = \"name\")] for custom type of database or #[table(json)]"
+            );
+            return syn::Error::new_spanned(&field.ty, message)
+                .to_compile_error()
+                .into();
+        }
+        // Serde type of `#[table(json)]` is stored as json
+        let sql_type = if is_json(field) {
+            ColumnType::Named("Json").to_tokens()
+        } else {
+            sql_type.to_tokens()
+        };
+        let primary_key = field_has_flag(field, "primary_key");
+        let unique = field_has_flag(field, "unique");
+        let default = match get_field_kind_str(field, "default") {
+            Some(default) => quote! { Some(#default.to_string()) },
+            None => quote! { None },
+        };
+        let check = match get_field_kind_str(field, "check") {
+            Some(check) => quote! { Some(#check.to_string()) },
+            None => quote! { None },
+        };
+        columns.push(quote! {
+            sql_macros::Column {
+                name: #name.to_string(),
+                sql_type: #sql_type,
+                nullable: #nullable,
+                primary_key: #primary_key,
+                unique: #unique,
+                default: #default,
+                check: #check,
+            }
+        });
+    }
+
+    let indexes = table.get_indexes(fields).into_iter().map(|index| {
+        let name = index.name;
+        let columns = index.columns;
+        let unique = index.unique;
+        let predicate = match index.predicate {
+            Some(predicate) => quote! { Some(#predicate.to_string()) },
+            None => quote! { None },
+        };
+        quote! {
+            sql_macros::Index {
+                name: #name.to_string(),
+                columns: vec![#(#columns.to_string()),*],
+                unique: #unique,
+                predicate: #predicate,
+            }
+        }
+    });
+
+    let token_stream = quote! {
+        impl sql_macros::SqlSchema for #struct_name {
+            fn schema() -> sql_macros::Schema {
+                sql_macros::Schema {
+                    table: #table_name.to_string(),
+                    columns: vec![
+                        #(#columns),*
+                    ],
+                    indexes: vec![
+                        #(#indexes),*
+                    ],
+                }
+            }
+        }
+    };
+    token_stream.into()
+}
@@ -0,0 +1,193 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::parser::{RowType, Table, fields_named_struct, get_sql_columns, scope_tenant};
+
+pub fn sql_search_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let table = Table::parse(input);
+    let error = table.get_error();
+    let struct_name = input.ident.clone();
+    let from_clause = table.get_from_clause();
+    let executor = table.get_executor();
+    let (read_param, read) = (executor.read_param(), executor.read());
+
+    let fields = fields_named_struct(input);
+    let tenant = table.get_tenant(fields);
+    let column = |name: &str| {
+        let field = fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+            .unwrap_or_else(|| panic!("Field {name} not contains in {struct_name}"));
+        table.get_column(field).expect("Named field")
+    };
+    let sql_columns = get_sql_columns(&table, fields).join(", ");
+
+    let searches = table.get_search();
+    if let Some((method_name, _)) = searches.iter().find(|(_, fields)| fields.is_empty()) {
+        panic!("Search {method_name} must has fields");
+    }
+
+    if table.is_sqlite() {
+        return sqlite_search(&table, input, &searches);
+    }
+
+    let language = table.get_language().unwrap_or("english".to_string());
+    let mut methods = vec![];
+    for (method_name, search_fields) in searches {
+        let mn = format_ident!("{method_name}");
+        // Example: to_tsvector('english', coalesce(title, '') || ' ' || coalesce(body, ''))
+        let document = search_fields
+            .iter()
+            .map(|name| format!("coalesce({}, '')", column(name)))
+            .collect::<Vec<String>>()
+            .join(" || ' ' || ");
+        let vector = format!("to_tsvector('{language}', {document})");
+        let ts_query = format!("websearch_to_tsquery('{language}', $1)");
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {vector} @@ {ts_query}");
+        // Example: search_posts(pool, query, limit, org_id) for `#[table(tenant = org_id)]`
+        let (query, params, binds) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &quote! { query: &str, limit: i64 },
+            &[quote! { query }, quote! { limit }],
+        );
+        let query = format!("{query} ORDER BY ts_rank({vector}, {ts_query}) DESC LIMIT $2");
+        let search = RowType::new(&struct_name, fields).query_as(&query, quote! { #(#binds),* });
+
+        methods.push(quote! {
+            #[doc=#query]
+            pub async fn #mn(#read_param, #params) -> Result<Vec<#struct_name>, #error> {
+                let object = #search.fetch_all(#read).await?;
+                Ok(object)
+            }
+        });
+    }
+
+    let token_stream = quote! {
+        impl #struct_name {
+            #(#methods)*
+        }
+    };
+    token_stream.into()
+}
+
+/// Search in FTS5 table `{table}_fts` with columns of all searches, `fts_ddl()` creates it with
+/// triggers which keep it in sync with table
+fn sqlite_search(
+    table: &Table,
+    input: &syn::DeriveInput,
+    searches: &[(String, Vec<String>)],
+) -> TokenStream {
+    let error = table.get_error();
+    let struct_name = input.ident.clone();
+    let table_name = table.get_name();
+    let from_clause = table.get_from_clause();
+    let fields = fields_named_struct(input);
+    let tenant = table.get_tenant(fields);
+    let sql_columns = get_sql_columns(table, fields).join(", ");
+    let fts = format!("{table_name}_fts");
+
+    // FTS5 has stemmer only for english, other languages are tokenized without stemming
+    let tokenize = match table.get_language().as_deref() {
+        None => String::new(),
+        Some("english") => ", tokenize='porter unicode61'".to_string(),
+        Some(language) => {
+            let message =
+                format!("SQLite FTS5 has no stemmer of {language}, remove language or use english");
+            return syn::Error::new_spanned(&struct_name, message)
+                .to_compile_error()
+                .into();
+        }
+    };
+    let mut fts_columns: Vec<&String> = vec![];
+    for name in searches.iter().flat_map(|(_, fields)| fields) {
+        if !fts_columns.contains(&name) {
+            fts_columns.push(name);
+        }
+    }
+    let columns = fts_columns
+        .iter()
+        .map(|name| name.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let new_values = fts_columns
+        .iter()
+        .map(|name| format!("new.{name}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let old_values = fts_columns
+        .iter()
+        .map(|name| format!("old.{name}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let insert = format!("INSERT INTO {fts}(rowid, {columns}) VALUES (new.rowid, {new_values});");
+    let delete = format!(
+        "INSERT INTO {fts}({fts}, rowid, {columns}) VALUES ('delete', old.rowid, {old_values});"
+    );
+    let fts_ddl = [
+        format!(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS {fts} USING fts5({columns}, content='{table_name}'{tokenize})"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts}_insert AFTER INSERT ON {table_name} BEGIN {insert} END"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts}_delete AFTER DELETE ON {table_name} BEGIN {delete} END"
+        ),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS {fts}_update AFTER UPDATE ON {table_name} BEGIN {delete} {insert} END"
+        ),
+    ];
+
+    let mut methods = vec![];
+    for (method_name, search_fields) in searches {
+        let mn = format_ident!("{method_name}");
+        // Example: {title body} : ("rust"), other columns of FTS5 table are not matched
+        let filter = format!("{{{}}}", search_fields.join(" "));
+        let matched = format!(
+            "(SELECT rowid, bm25({fts}) AS bm25 FROM {fts} WHERE {fts} MATCH ?1) AS matched"
+        );
+        // Example: search_notes(pool, query, limit, org_id) for `#[table(tenant = org_id)]`
+        let (tenant_filter, tenant_params, tenant_bind) = match &tenant {
+            Some(tenant) => (
+                format!(" WHERE {} = ?3", tenant.column),
+                Some(tenant.param()),
+                Some(tenant.bind()),
+            ),
+            None => (String::new(), None, None),
+        };
+        let query = format!(
+            "SELECT {sql_columns} FROM {from_clause} JOIN {matched} ON matched.rowid = {table_name}.rowid{tenant_filter} ORDER BY matched.bm25 LIMIT ?2"
+        );
+        let binds = [quote! { query }, quote! { limit }]
+            .into_iter()
+            .chain(tenant_bind)
+            .collect::<Vec<_>>();
+        let search = RowType::new(&struct_name, fields).query_as(&query, quote! { #(#binds),* });
+
+        methods.push(quote! {
+            #[doc=#query]
+            pub async fn #mn(pool: &sqlx::SqlitePool, query: &str, limit: i64, #tenant_params) -> Result<Vec<#struct_name>, #error> {
+                // Terms of user input are quoted, FTS5 syntax of input is not applied
+                let Some(query) = sql_macros::fts5_query(#filter, query) else {
+                    return Ok(vec![]);
+                };
+                let object = #search.fetch_all(pool).await?;
+                Ok(object)
+            }
+        });
+    }
+
+    let token_stream = quote! {
+        impl #struct_name {
+            /// Statements to create FTS5 table of searches and triggers which keep it in sync
+            pub fn fts_ddl() -> Vec<&'static str> {
+                vec![#(#fts_ddl),*]
+            }
+
+            #(#methods)*
+        }
+    };
+    token_stream.into()
+}
@@ -1,57 +1,116 @@
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
 use quote::quote;
+use syn::ext::IdentExt;
 
 use crate::parser::{
-    Table, extract_fields_as_params, fields_named_struct, generate_sql_params_condition,
-    get_filters, get_method_params, get_sql_columns, parse_fields_with_type,
+    RowType, Table, extract_fields_as_params, fields_named_struct, generate_projection_method,
+    generate_sql_params_condition, get_bind_params, get_filters, get_method_params,
+    get_sql_columns, parse_fields_with_type, required_method_name, scope_tenant,
 };
 
 fn generate_method(
     method_name: &str,
-    struct_name: &proc_macro2::Ident,
+    struct_name: &RowType,
     params: &proc_macro2::TokenStream,
     query: &str,
-    filter_fields: &Vec<proc_macro2::Ident>,
+    filter_fields: &[proc_macro2::TokenStream],
+    table: &Table,
 ) -> proc_macro2::TokenStream {
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (read_param, read, lock_param) = (
+        executor.read_param(),
+        executor.read(),
+        executor.lock_param(),
+    );
     let mn = syn::parse_str::<Ident>(method_name).expect("Failed to parse code string");
+    // Example: select_by_id_for_update(tx, id), only in transaction
+    let locked_method = table.get_for_update().map(|for_update| {
+        let mn = syn::parse_str::<Ident>(&format!("{method_name}_for_update"))
+            .expect("Failed to parse code string");
+        let query = format!("{query} {for_update}");
+        let select = struct_name.query_as(&query, quote! { #(#filter_fields),* });
+        quote! {
+            #[doc=#query]
+            pub async fn #mn(
+                #lock_param,
+                #params
+            ) -> Result<Option<#struct_name>, #error> {
+                let object = #select.fetch_optional(&mut **tx).await?;
+                Ok(object)
+            }
+        }
+    });
+    let select = struct_name.query_as(query, quote! { #(#filter_fields),* });
+    // Example: select_by_id -> select_one_by_id with `#[table(required)]`
+    let required_method = table.has_required().then(|| {
+        let required_mn = required_method_name("select", method_name);
+        quote! {
+            /// Return error of `sqlx::Error::RowNotFound` if row is not found
+            #[doc=#query]
+            pub async fn #required_mn(#read_param, #params) -> Result<#struct_name, #error> {
+                let object = #select.fetch_one(#read).await?;
+                Ok(object)
+            }
+        }
+    });
     quote! {
         #[doc=#query]
-        pub async fn #mn(pool: &sqlx::PgPool, #params) -> Result<Option<#struct_name>, sqlx::Error> {
-            let object = sqlx::query_as!(
-                #struct_name,
-                #query,
-                #(#filter_fields),*
-            )
-            .fetch_optional(pool)
-            .await?;
+        pub async fn #mn(#read_param, #params) -> Result<Option<#struct_name>, #error> {
+            let object = #select.fetch_optional(#read).await?;
             Ok(object)
         }
+
+        #required_method
+
+        #locked_method
     }
 }
 
 pub fn sql_select_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table = Table::parse(input);
     let struct_name = input.ident.clone();
-    let table_name = table.get_name();
+    let from_clause = table.get_from_clause();
 
     let fields = fields_named_struct(input);
-    let fields_with_type = parse_fields_with_type(fields, "select");
-    let sql_columns = get_sql_columns(fields).join(", ");
+    let tenant = table.get_tenant(fields);
+    let mut fields_with_type = parse_fields_with_type(fields, "select");
+    for field_with_type in parse_fields_with_type(fields, "unique") {
+        if !fields_with_type
+            .iter()
+            .any(|(name_field, _)| *name_field == field_with_type.0)
+        {
+            fields_with_type.push(field_with_type);
+        }
+    }
+    let sql_columns = get_sql_columns(&table, fields).join(", ");
+    let row_type = RowType::new(&struct_name, fields);
+
+    let field_filters = get_filters(fields_with_type.clone());
 
     let mut methods = vec![];
     for field_with_type in fields_with_type {
         let params = get_method_params(vec![field_with_type.clone()]);
         let filter_fields = get_filters(vec![field_with_type.clone()]);
-        let sql_filters = generate_sql_params_condition(&filter_fields);
-        let query = format!("SELECT {sql_columns} FROM {table_name} WHERE {sql_filters}");
+        let sql_filters =
+            generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields));
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {sql_filters}");
 
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &params,
+            &get_bind_params(fields, std::slice::from_ref(&field_with_type)),
+        );
         methods.push(generate_method(
-            &format!("select_by_{}", field_with_type.0),
-            &struct_name,
+            &format!("select_by_{}", field_with_type.0.unraw()),
+            &row_type,
             &params,
             &query,
-            &filter_fields,
+            &bind_params,
+            &table,
         ));
     }
 
@@ -69,15 +128,51 @@ pub fn sql_select_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
             )
         }
         let params = get_method_params(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, &fields_with_type);
         let filter_fields = get_filters(fields_with_type);
-        let sql_filters = generate_sql_params_condition(&filter_fields);
-        let query = format!("SELECT {sql_columns} FROM {table_name} WHERE {sql_filters}");
+        let sql_filters =
+            generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields));
+        if let Some(method) = generate_projection_method(
+            "select",
+            &method_name,
+            &params,
+            &sql_filters,
+            &filter_fields,
+            &table,
+            tenant.as_ref(),
+        ) {
+            methods.push(method);
+            continue;
+        }
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {sql_filters}");
+        let (query, params, bind_params) =
+            scope_tenant(tenant.as_ref(), &query, true, &params, &bind_params);
         methods.push(generate_method(
             &method_name,
-            &struct_name,
+            &row_type,
             &params,
             &query,
-            &filter_fields,
+            &bind_params,
+            &table,
+        ));
+    }
+
+    for index in table.get_method_indexes(fields, true, &field_filters) {
+        let fields_with_type = index.fields_with_type(&ff);
+        let params = get_method_params(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, &fields_with_type);
+        let filter_fields = get_filters(fields_with_type);
+        let sql_filters = index.sql_condition(&table.qualify_columns(fields, &filter_fields));
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {sql_filters}");
+        let (query, params, bind_params) =
+            scope_tenant(tenant.as_ref(), &query, true, &params, &bind_params);
+        methods.push(generate_method(
+            &format!("select_by_{}", index.method_suffix()),
+            &row_type,
+            &params,
+            &query,
+            &bind_params,
+            &table,
         ));
     }
 
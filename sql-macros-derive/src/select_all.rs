@@ -1,27 +1,45 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
-use crate::parser::{Table, fields_named_struct, get_sql_columns};
+use crate::parser::{RowType, Table, fields_named_struct, get_sql_columns, scope_tenant};
 
 pub fn sql_select_all_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table = Table::parse(input);
+    let error = table.get_error();
     let struct_name = input.ident.clone();
-    let table_name = table.get_name();
+    let from_clause = table.get_from_clause();
+    let executor = table.get_executor();
+    let (read_param, read) = (executor.read_param(), executor.read());
 
     let fields = fields_named_struct(input);
-    let sql_columns = get_sql_columns(fields).join(", ");
+    let sql_columns = get_sql_columns(&table, fields).join(", ");
 
-    let query = format!("SELECT {sql_columns} FROM {table_name}");
+    let query = format!("SELECT {sql_columns} FROM {from_clause}");
+    // Example: select_all(pool, org_id) for `#[table(tenant = org_id)]`
+    let (query, params, binds) = scope_tenant(
+        table.get_tenant(fields).as_ref(),
+        &query,
+        false,
+        &quote! {},
+        &[],
+    );
+    let select = RowType::new(&struct_name, fields).query_as(&query, quote! { #(#binds),* });
 
     let token_stream = quote! {
         impl #struct_name {
             #[doc=#query]
-            pub async fn select_all(pool: &sqlx::PgPool) -> Result<Vec<#struct_name>, sqlx::Error> {
-                let object = sqlx::query_as!(#struct_name, #query)
-                    .fetch_all(pool)
-                    .await?;
+            pub async fn select_all(#read_param, #params) -> Result<Vec<#struct_name>, #error> {
+                let object = #select.fetch_all(#read).await?;
                 Ok(object)
             }
+
+            #[doc=#query]
+            pub fn stream_all(
+                #read_param,
+                #params
+            ) -> impl sql_macros::Stream<Item = Result<#struct_name, #error>> + Send + '_ {
+                sql_macros::err_into(#select.fetch(#read))
+            }
         }
     };
     token_stream.into()
@@ -1,57 +1,234 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 
 use crate::parser::{
-    Table, extract_fields_as_params, fields_named_struct, generate_sql_params_condition,
-    get_filters, get_method_params, get_sql_columns, parse_fields_with_type,
+    RowType, Table, array_element, extract_fields_as_params, field_has_flag, fields_named_struct,
+    generate_projection_method, generate_sql_params_condition, get_bind_params, get_filters,
+    get_method_params, get_sql_columns, is_custom_type, is_json, parse_fields_with_type,
+    scope_tenant,
 };
 
 fn generate_method(
     method_name: &str,
-    struct_name: &proc_macro2::Ident,
+    struct_name: &RowType,
     params: &proc_macro2::TokenStream,
     query: &str,
-    filter_fields: &Vec<proc_macro2::Ident>,
+    filter_fields: &[proc_macro2::TokenStream],
+    table: &Table,
+    lifetime: Option<&syn::Lifetime>,
 ) -> proc_macro2::TokenStream {
+    let error = table.get_error();
+    let executor = table.get_executor();
+    let (read_param, read, lock_param) = (
+        executor.read_param(),
+        executor.read(),
+        executor.lock_param(),
+    );
+    // Example: select_many_by_tags_contains<'a>(pool: &'a sqlx::PgPool, values: &'a [String]),
+    // stream borrows both params, so their lifetime is named
+    let (generics, stream_read_param, stream_lifetime) = match lifetime {
+        Some(lifetime) => (
+            quote! { <#lifetime> },
+            executor.read_param_for(lifetime),
+            quote! { #lifetime },
+        ),
+        None => (quote! {}, read_param.clone(), quote! { '_ }),
+    };
     let mn =
         syn::parse_str::<proc_macro2::Ident>(method_name).expect("Failed to parse code string");
+    // Example: select_many_by_email -> stream_many_by_email
+    let stream_mn = syn::parse_str::<proc_macro2::Ident>(&method_name.replacen(
+        "select_many",
+        "stream_many",
+        1,
+    ))
+    .expect("Failed to parse code string");
+    let stream_mn = if stream_mn == mn {
+        syn::parse_str::<proc_macro2::Ident>(&format!("stream_{method_name}"))
+            .expect("Failed to parse code string")
+    } else {
+        stream_mn
+    };
+    // Example: select_many_by_status_for_update(tx, status, limit), only in transaction
+    let locked_method = table.get_for_update().map(|for_update| {
+        let mn = syn::parse_str::<proc_macro2::Ident>(&format!("{method_name}_for_update"))
+            .expect("Failed to parse code string");
+        let query = format!("{query} LIMIT ${} {for_update}", filter_fields.len() + 1);
+        let select = struct_name.query_as(&query, quote! { #(#filter_fields,)* limit });
+        quote! {
+            #[doc=#query]
+            pub async fn #mn #generics(
+                #lock_param,
+                #params,
+                limit: i64,
+            ) -> Result<Vec<#struct_name>, #error> {
+                let object = #select.fetch_all(&mut **tx).await?;
+                Ok(object)
+            }
+        }
+    });
+    let select = struct_name.query_as(query, quote! { #(#filter_fields),* });
     quote! {
         #[doc=#query]
-        pub async fn #mn(pool: &sqlx::PgPool, #params) -> Result<Vec<#struct_name>, sqlx::Error> {
-            let object = sqlx::query_as!(
-                #struct_name,
-                #query,
-                #(#filter_fields),*
-            )
-            .fetch_all(pool)
-            .await?;
+        pub async fn #mn #generics(#read_param, #params) -> Result<Vec<#struct_name>, #error> {
+            let object = #select.fetch_all(#read).await?;
             Ok(object)
         }
+
+        #[doc=#query]
+        pub fn #stream_mn #generics(
+            #stream_read_param,
+            #params
+        ) -> impl sql_macros::Stream<Item = Result<#struct_name, #error>> + Send + #stream_lifetime {
+            sql_macros::err_into(#select.fetch(#read))
+        }
+
+        #locked_method
     }
 }
 
 pub fn sql_select_many_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table = Table::parse(input);
     let struct_name = input.ident.clone();
-    let table_name = table.get_name();
+    let from_clause = table.get_from_clause();
 
     let fields = fields_named_struct(input);
-    let fields_with_type = parse_fields_with_type(fields, "select_many");
-    let sql_columns = get_sql_columns(fields).join(", ");
+    let tenant = table.get_tenant(fields);
+    let mut fields_with_type = parse_fields_with_type(fields, "select_many");
+    for field_with_type in parse_fields_with_type(fields, "index") {
+        if !fields_with_type
+            .iter()
+            .any(|(name_field, _)| *name_field == field_with_type.0)
+        {
+            fields_with_type.push(field_with_type);
+        }
+    }
+    let sql_columns = get_sql_columns(&table, fields).join(", ");
+    let row_type = RowType::new(&struct_name, fields);
+
+    let field_filters = get_filters(fields_with_type.clone());
 
     let mut methods = vec![];
     for field_with_type in fields_with_type {
         let params = get_method_params(vec![field_with_type.clone()]);
         let filter_fields = get_filters(vec![field_with_type.clone()]);
-        let sql_filters = generate_sql_params_condition(&filter_fields);
-        let query = format!("SELECT {sql_columns} FROM {table_name} WHERE {sql_filters}");
+        let sql_filters =
+            generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields));
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {sql_filters}");
 
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &params,
+            &get_bind_params(fields, std::slice::from_ref(&field_with_type)),
+        );
         methods.push(generate_method(
-            &format!("select_many_by_{}", field_with_type.0),
-            &struct_name,
+            &format!("select_many_by_{}", field_with_type.0.unraw()),
+            &row_type,
             &params,
             &query,
-            &filter_fields,
+            &bind_params,
+            &table,
+            None,
+        ));
+    }
+
+    // Operators of json and arrays are generated only for fields of `#[table(contains)]`
+    let contains = fields
+        .iter()
+        .filter(|field| field_has_flag(field, "contains"))
+        .collect::<Vec<_>>();
+    if let Some(field) = contains
+        .iter()
+        .find(|field| !is_json(field) && array_element(field).is_none())
+    {
+        return syn::Error::new_spanned(
+            field,
+            "#[table(contains)] requires field of #[table(json)] or Vec<T>",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Example: select_many_by_settings_key(pool, "theme".into(), "dark".into()),
+    // select_many_by_settings_contains(pool, serde_json::json!({"theme": "dark"}))
+    for field in contains.iter().filter(|field| is_json(field)) {
+        let Some(ident) = field.ident.as_ref().map(IdentExt::unraw) else {
+            continue;
+        };
+        let column = table.get_column_source(field).unwrap_or(ident.to_string());
+
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {column}->>$1 = $2");
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &quote! { key: String, value: String },
+            &[quote! { key }, quote! { value }],
+        );
+        methods.push(generate_method(
+            &format!("select_many_by_{ident}_key"),
+            &row_type,
+            &params,
+            &query,
+            &bind_params,
+            &table,
+            None,
+        ));
+
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {column} @> $1");
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &quote! { value: sqlx::types::JsonValue },
+            &[quote! { value }],
+        );
+        methods.push(generate_method(
+            &format!("select_many_by_{ident}_contains"),
+            &row_type,
+            &params,
+            &query,
+            &bind_params,
+            &table,
+            None,
+        ));
+    }
+
+    // Example: select_many_by_tags_contains(pool, &["rust".to_string()])
+    let lifetime = syn::Lifetime::new("'a", proc_macro2::Span::call_site());
+    for field in &contains {
+        let (Some(ident), Some(element)) = (
+            field.ident.as_ref().map(IdentExt::unraw),
+            array_element(field),
+        ) else {
+            continue;
+        };
+        let column = table.get_column_source(field).unwrap_or(ident.to_string());
+        let values = if is_custom_type(field) {
+            quote! { values as _ }
+        } else {
+            quote! { values }
+        };
+
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {column} @> $1");
+        let (query, params, bind_params) = scope_tenant(
+            tenant.as_ref(),
+            &query,
+            true,
+            &quote! { values: &'a [#element] },
+            &[values],
+        );
+        methods.push(generate_method(
+            &format!("select_many_by_{ident}_contains"),
+            &row_type,
+            &params,
+            &query,
+            &bind_params,
+            &table,
+            Some(&lifetime),
         ));
     }
 
@@ -69,15 +246,53 @@ pub fn sql_select_many_macro_derive(input: &mut syn::DeriveInput) -> TokenStream
             )
         }
         let params = get_method_params(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, &fields_with_type);
         let filter_fields = get_filters(fields_with_type);
-        let sql_filters = generate_sql_params_condition(&filter_fields);
-        let query = format!("SELECT {sql_columns} FROM {table_name} WHERE {sql_filters}");
+        let sql_filters =
+            generate_sql_params_condition(&table.qualify_columns(fields, &filter_fields));
+        if let Some(method) = generate_projection_method(
+            "select_many",
+            &method_name,
+            &params,
+            &sql_filters,
+            &filter_fields,
+            &table,
+            tenant.as_ref(),
+        ) {
+            methods.push(method);
+            continue;
+        }
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {sql_filters}");
+        let (query, params, bind_params) =
+            scope_tenant(tenant.as_ref(), &query, true, &params, &bind_params);
         methods.push(generate_method(
             &method_name,
-            &struct_name,
+            &row_type,
             &params,
             &query,
-            &filter_fields,
+            &bind_params,
+            &table,
+            None,
+        ));
+    }
+
+    for index in table.get_method_indexes(fields, false, &field_filters) {
+        let fields_with_type = index.fields_with_type(&ff);
+        let params = get_method_params(fields_with_type.clone());
+        let bind_params = get_bind_params(fields, &fields_with_type);
+        let filter_fields = get_filters(fields_with_type);
+        let sql_filters = index.sql_condition(&table.qualify_columns(fields, &filter_fields));
+        let query = format!("SELECT {sql_columns} FROM {from_clause} WHERE {sql_filters}");
+        let (query, params, bind_params) =
+            scope_tenant(tenant.as_ref(), &query, true, &params, &bind_params);
+        methods.push(generate_method(
+            &format!("select_many_by_{}", index.method_suffix()),
+            &row_type,
+            &params,
+            &query,
+            &bind_params,
+            &table,
+            None,
         ));
     }
 
@@ -1,23 +1,62 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 
-use crate::parser::{Table, fields_named_struct, get_sql_columns, get_struct_fields};
+use crate::parser::{
+    Table, fields_named_struct, get_runtime_columns, get_sql_columns, get_struct_fields,
+};
 
 pub fn sql_table_macro_derive(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
     let table = Table::parse(input);
     let struct_name = input.ident.clone();
     let table_name = table.get_name();
+    let from_clause = table.get_from_clause();
 
     let fields = fields_named_struct(input);
 
-    let sql_columns = get_sql_columns(fields);
+    let sql_columns = get_sql_columns(&table, fields);
+    let select_columns = get_runtime_columns(&table, fields).join(", ");
     let struct_fields = get_struct_fields(fields);
+    // Example: fn tenant() -> Option<&'static str> { Some("org_id") }
+    let tenant = table.get_tenant(fields).map(|tenant| {
+        let column = tenant.column;
+        quote! {
+            fn tenant() -> Option<&'static str> {
+                Some(#column)
+            }
+        }
+    });
+
+    // Example: ("email", "users.email") for struct with `#[table(from = orders, join(users, ..))]`
+    let order_columns = fields.iter().filter_map(|field| {
+        let name = field.ident.as_ref()?.unraw().to_string();
+        let column = table.get_column_source(field).unwrap_or(name.clone());
+        Some(quote! { (#name, #column) })
+    });
+
+    // Example: pub const EMAIL: sql_macros::TypedColumn<User, String> = sql_macros::TypedColumn::new("email");
+    // or `TypedColumn::with_source("email", "users.email")` for view, `r#type` is `TYPE`
+    let columns = fields.iter().filter_map(|field| {
+        let name = field.ident.as_ref()?.unraw().to_string();
+        let const_name = format_ident!("{}", name.to_uppercase());
+        let ty = &field.ty;
+        let column = match table.get_column_source(field) {
+            Some(column) => quote! { sql_macros::TypedColumn::with_source(#name, #column) },
+            None => quote! { sql_macros::TypedColumn::new(#name) },
+        };
+        Some(quote! {
+            pub const #const_name: sql_macros::TypedColumn<#struct_name, #ty> = #column;
+        })
+    });
 
     let token_stream = quote! {
         impl sql_macros::SqlTable for #struct_name {
             fn name() -> &'static str {
                 #table_name
             }
+            fn from_clause() -> &'static str {
+                #from_clause
+            }
             fn fields() -> Vec<&'static str> {
                 vec![
                     #(#struct_fields),*
@@ -28,6 +67,19 @@ pub fn sql_table_macro_derive(input: &syn::DeriveInput) -> syn::Result<TokenStre
                     #(#sql_columns),*
                 ]
             }
+            fn select_columns() -> String {
+                #select_columns.to_string()
+            }
+            fn columns() -> Vec<(&'static str, &'static str)> {
+                vec![
+                    #(#order_columns),*
+                ]
+            }
+            #tenant
+        }
+
+        impl #struct_name {
+            #(#columns)*
         }
     };
     Ok(token_stream.into())
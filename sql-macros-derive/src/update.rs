@@ -1,32 +1,67 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 
-use crate::parser::{Table, fields_named_struct, get_filters, parse_fields_with_type};
+use crate::parser::{
+    RowType, Table, array_element, fields_named_struct, get_bind_params, get_filters,
+    get_ident_bind, get_method_params, is_custom_type, is_json, parse_fields_with_type,
+    scope_tenant,
+};
+use crate::schema::pg_type_name;
 
 pub fn sql_update_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
     let table = Table::parse(input);
+    let error = table.get_error();
     let struct_name = input.ident.clone();
     let table_name = table.get_name();
+    let executor = table.get_executor();
+    let (write_param, write) = (executor.write_param(), executor.write());
 
     let fields = fields_named_struct(input);
+    // Tenant is not updated, rows are filtered by param of method
+    let tenant = table.get_tenant(fields);
+    let is_tenant = |ident: &proc_macro2::Ident| tenant.as_ref().is_some_and(|t| t.ident == *ident);
 
-    let fields_with_type = parse_fields_with_type(fields, "update");
+    let fields_with_type = parse_fields_with_type(fields, "update")
+        .into_iter()
+        .filter(|(ident, _)| !is_tenant(ident))
+        .collect::<Vec<_>>();
     let filters = get_filters(fields_with_type.clone());
-    let count_columns = fields.len() - filters.len();
+    if filters.is_empty() {
+        return syn::Error::new_spanned(
+            &struct_name,
+            format!("{struct_name} must have a key of update `#[table(update)]`"),
+        )
+        .to_compile_error()
+        .into();
+    }
 
     let idents = fields
         .iter()
         .filter(|field| field.ident.is_some())
         .map(|field| field.clone().ident.unwrap());
-    let columns = idents.clone().filter(|ident| !filters.contains(ident));
+    let columns = idents
+        .clone()
+        .filter(|ident| !filters.contains(ident) && !is_tenant(ident))
+        .collect::<Vec<_>>();
+    let count_columns = columns.len();
 
+    // Example: name of `#[table(source = users.full_name)]` is `full_name`
+    let column_name = |ident: &proc_macro2::Ident| {
+        fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(ident))
+            .and_then(|field| table.get_column_name(field))
+            .expect("Field of struct")
+    };
     let sql_column = columns
-        .clone()
+        .iter()
         .enumerate()
-        .map(|(index, column)| format!("{column}=${}", index + 1))
+        .map(|(index, column)| format!("{}=${}", column_name(column), index + 1))
         .collect::<Vec<String>>()
         .join(", ");
-    let sql_filters = filters
+    let sql_filters = table
+        .qualify_columns(fields, &filters)
         .iter()
         .enumerate()
         .map(|(index, column)| format!("{column}=${}", count_columns + index + 1))
@@ -39,46 +74,218 @@ pub fn sql_update_macro_derive(input: &mut syn::DeriveInput) -> TokenStream {
         .unwrap_or_default();
 
     let query = format!("UPDATE {table_name} SET {sql_column}{spec_columns} WHERE {sql_filters}");
-    let returning = table.get_return_fields().unwrap_or("*".to_string());
 
-    let token_stream = if let Some(return_type) = table.get_return_type() {
-        let type_param =
-            syn::parse_str::<syn::Type>(&return_type).expect("Failed to parse input string");
+    // Rows are passed as arrays of columns, example:
+    // UPDATE users SET email=data.email FROM UNNEST($1::text[], $2::int4[]) AS data(email, id) WHERE users.id=data.id
+    let many_idents = columns.iter().chain(filters.iter()).collect::<Vec<_>>();
+    let many_fields = many_idents
+        .iter()
+        .map(|ident| {
+            fields
+                .iter()
+                .find(|field| field.ident.as_ref() == Some(*ident))
+                .expect("Field of struct")
+        })
+        .collect::<Vec<_>>();
+    // UNNEST flattens arrays, so rows with array columns can't be passed by it,
+    // values of `#[table(json)]` are not wrapped into arrays and custom types are casted
+    // only by name of `#[table(sql_type = "user_role")]`
+    let many_types = many_fields
+        .iter()
+        .map(|field| pg_type_name(field).filter(|_| !is_json(field)))
+        .collect::<Option<Vec<_>>>();
+    let update_many = if let Some(many_types) = many_types {
+        let unnest = many_types
+            .iter()
+            .enumerate()
+            .map(|(index, pg_type)| format!("${}::{pg_type}[]", index + 1))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let many_columns = columns
+            .iter()
+            .map(|column| format!("{0}=data.{0}", column_name(column)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        // Example: orders.id=data.id for `#[table(from = orders)]`
+        let many_filters = filters
+            .iter()
+            .map(|column| {
+                let name = column_name(column);
+                let source = fields
+                    .iter()
+                    .find(|field| field.ident.as_ref() == Some(column))
+                    .and_then(|field| table.get_column_source(field))
+                    .unwrap_or(format!("{table_name}.{name}"));
+                format!("{source}=data.{name}")
+            })
+            .collect::<Vec<String>>()
+            .join(" AND ");
+        let many_names = many_idents
+            .iter()
+            .map(|ident| column_name(ident))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let many_query = format!(
+            "UPDATE {table_name} SET {many_columns}{spec_columns} FROM UNNEST({unnest}) AS data({many_names}) WHERE {many_filters}"
+        );
+        // Locals are prefixed, so fields `rows` and `conn` don't shadow params
+        let many_locals = many_idents
+            .iter()
+            .map(|ident| format_ident!("rows_{}", ident.unraw()))
+            .collect::<Vec<_>>();
+        let many_binds = many_locals
+            .iter()
+            .map(|local| quote! { #local as _ })
+            .collect::<Vec<_>>();
+        let (many_query, many_params, many_binds) = scope_tenant(
+            tenant.as_ref(),
+            &many_query,
+            true,
+            &quote! { rows: &[#struct_name] },
+            &many_binds,
+        );
+        quote! {
+            #[doc=#many_query]
+            pub async fn update_many(#write_param, #many_params) -> Result<sqlx::any::AnyQueryResult, #error>
+            {
+                #(
+                    let #many_locals = rows.iter().map(|row| &row.#many_idents).collect::<Vec<_>>();
+                )*
+                let query_result = sqlx::query!(
+                    #many_query,
+                    #(#many_binds),* // Example: columns as _
+                )
+                .execute(#write)
+                .await?;
+                Ok(query_result.into())
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Example: self.email as _, sqlx::types::Json(&self.settings) as _
+    let binds = columns
+        .iter()
+        .chain(filters.iter())
+        .map(|ident| get_ident_bind(fields, ident))
+        .collect::<Vec<_>>();
+    // Example: update(&self, conn, org_id) for `#[table(tenant = org_id)]`
+    let (query, tenant_params, binds) =
+        scope_tenant(tenant.as_ref(), &query, true, &quote! {}, &binds);
+    // Example: append_tags(conn, id, tag)
+    // UPDATE users SET tags=array_append(tags, $1) WHERE id=$2
+    let key_params = get_method_params(fields_with_type.clone());
+    let key_binds = get_bind_params(fields, &fields_with_type);
+    let key_filters = table
+        .qualify_columns(fields, &filters)
+        .iter()
+        .enumerate()
+        .map(|(index, column)| format!("{column}=${}", index + 2))
+        .collect::<Vec<String>>()
+        .join(" AND ");
+    let mut array_methods = vec![];
+    for field in fields {
+        let (Some(ident), Some(element)) = (field.ident.as_ref(), array_element(field)) else {
+            continue;
+        };
+        // Example: SET tags=array_append(orders.tags, $1) for `#[table(from = orders)]`
+        let name = table.get_column_name(field).expect("Column of named field");
+        let source = table.get_column_source(field).unwrap_or(name.clone());
+        // Example: value as _ for array of enum
+        let value = if is_custom_type(field) {
+            quote! { value as _ }
+        } else {
+            quote! { value }
+        };
+        for (prefix, function) in [("append", "array_append"), ("remove", "array_remove")] {
+            let mn = format_ident!("{prefix}_{}", ident.unraw());
+            let query = format!(
+                "UPDATE {table_name} SET {name}={function}({source}, $1){spec_columns} WHERE {key_filters}"
+            );
+            let (query, params, binds) = scope_tenant(
+                tenant.as_ref(),
+                &query,
+                true,
+                &quote! { #key_params, value: #element },
+                &[vec![value.clone()], key_binds.clone()].concat(),
+            );
+            array_methods.push(quote! {
+                #[doc=#query]
+                pub async fn #mn(#write_param, #params) -> Result<sqlx::any::AnyQueryResult, #error>
+                {
+                    let query_result = sqlx::query!(
+                        #query,
+                        #(#binds),*
+                    )
+                    .execute(#write)
+                    .await?;
+                    Ok(query_result.into())
+                }
+            });
+        }
+    }
+
+    let returning = table.get_returning(&struct_name, fields);
 
+    let token_stream = if let Some(type_param) = RowType::returning(&table, &struct_name, fields) {
         let query = format!("{query} RETURNING {returning}");
+        let update = type_param.query_as(&query, quote! { #(#binds),* });
 
         quote! {
             impl #struct_name {
                 #[doc=#query]
-                pub async fn update(&self, conn: &mut sqlx::PgConnection) -> Result<#type_param, sqlx::Error>
+                pub async fn update(&self, #write_param, #tenant_params) -> Result<#type_param, #error>
                 {
-                    let object = sqlx::query_as!(
-                        #type_param,
-                        #query,
-                        #(self.#columns as _),*, // Example: fields as _
-                        #(self.#filters as _),* // Example: fields as _
-                    )
-                    .fetch_one(&mut *conn)
-                    .await?;
+                    let object = #update.fetch_one(#write).await?;
                     Ok(object)
                 }
+
+                #update_many
+
+                #(#array_methods)*
             }
         }
     } else {
+        // `update` with `RETURNING` already returns error if row is not found
+        let required_method = table.has_required().then(|| {
+            quote! {
+                /// Return error of `sqlx::Error::RowNotFound` if no rows are updated
+                #[doc=#query]
+                pub async fn update_one(&self, #write_param, #tenant_params) -> Result<sqlx::any::AnyQueryResult, #error>
+                {
+                    let query_result = sqlx::query!(
+                        #query,
+                        #(#binds),* // Example: fields as _
+                    )
+                    .execute(#write)
+                    .await?;
+                    if query_result.rows_affected() == 0 {
+                        return Err(sqlx::Error::RowNotFound.into());
+                    }
+                    Ok(query_result.into())
+                }
+            }
+        });
         quote! {
             impl #struct_name {
                 #[doc=#query]
-                pub async fn update(&self, conn: &mut sqlx::PgConnection) -> Result<sqlx::any::AnyQueryResult, sqlx::Error>
+                pub async fn update(&self, #write_param, #tenant_params) -> Result<sqlx::any::AnyQueryResult, #error>
                 {
                     let query_result = sqlx::query!(
                         #query,
-                        #(self.#columns as _),*, // Example: fields as _
-                        #(self.#filters as _),* // Example: fields as _
+                        #(#binds),* // Example: fields as _
                     )
-                    .execute(&mut *conn)
+                    .execute(#write)
                     .await?;
                     Ok(query_result.into())
                 }
+
+                #required_method
+
+                #update_many
+
+                #(#array_methods)*
             }
         }
     };
@@ -0,0 +1,89 @@
+//! Generate migration from declared schema file and stored snapshot.
+//!
+//! Declared schema is written by the application, example:
+//! `sql_macros::write_snapshot("schema.json", &[User::schema(), Post::schema()])`
+//!
+//! ```sh
+//! sql-macros-migrate --schema schema.json --snapshot migrations/snapshot.json --dir migrations --name add_users
+//! ```
+
+use std::process::ExitCode;
+
+use sql_macros_core::{Dialect, read_schema, write_migration};
+
+const USAGE: &str = "Usage: sql-macros-migrate --schema <file> --snapshot <file> [--dir <migrations>] [--name <description>] [--dialect postgres|mysql|sqlite]";
+
+struct Args {
+    schema: String,
+    snapshot: String,
+    dir: String,
+    name: String,
+    dialect: Dialect,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut schema = None;
+    let mut snapshot = None;
+    let mut dir = "migrations".to_string();
+    let mut name = "schema".to_string();
+    let mut dialect = Dialect::Postgres;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {arg}"))?;
+        match arg.as_str() {
+            "--schema" => schema = Some(value),
+            "--snapshot" => snapshot = Some(value),
+            "--dir" => dir = value,
+            "--name" => name = value,
+            "--dialect" => {
+                dialect = match value.as_str() {
+                    "postgres" => Dialect::Postgres,
+                    "mysql" => Dialect::MySql,
+                    "sqlite" => Dialect::Sqlite,
+                    _ => return Err(format!("Unknown dialect {value}")),
+                }
+            }
+            _ => return Err(format!("Unknown argument {arg}")),
+        }
+    }
+
+    Ok(Args {
+        schema: schema.ok_or("Missing --schema")?,
+        snapshot: snapshot.ok_or("Missing --snapshot")?,
+        dir,
+        name,
+        dialect,
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = read_schema(&args.schema).and_then(|declared| {
+        write_migration(
+            declared,
+            &args.snapshot,
+            &args.dir,
+            &args.name,
+            args.dialect,
+        )
+    });
+    match result {
+        Ok(Some(path)) => println!("Created {}", path.display()),
+        Ok(None) => println!("No changes"),
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use sql_macros::SqlAggregate;
+
+#[derive(SqlAggregate)]
+#[table(name = aggregate_posts)]
+#[table(aggregate = revenue_by_user(sum(amount), avg(amount), count(*), group_by(user_id)))]
+#[table(aggregate = range_by_user(min(amount), max(amount), filter(title), group_by(user_id)))]
+#[table(aggregate = totals(sum(amount), count(title)))]
+pub struct Post {
+    pub id: i32,
+    pub user_id: i32,
+    pub title: String,
+    pub amount: i32,
+}
+
+#[tokio::test]
+async fn aggregates_are_grouped() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    sqlx::query("DELETE FROM aggregate_posts")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO aggregate_posts (user_id, title, amount) VALUES
+            (1, 'book', 10), (1, 'pen', 20), (2, 'book', 5)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let rows = Post::revenue_by_user(&pool).await.unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        (rows[0].user_id, rows[0].sum_amount, rows[0].count),
+        (1, Some(30), 2)
+    );
+    assert_eq!(rows[0].avg_amount, Some(15.0));
+    assert_eq!((rows[1].user_id, rows[1].sum_amount), (2, Some(5)));
+
+    let rows = Post::range_by_user(&pool, "book".to_string())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        (rows[0].min_amount, rows[0].max_amount),
+        (Some(10), Some(10))
+    );
+
+    let rows = Post::totals(&pool).await.unwrap();
+    assert_eq!((rows[0].sum_amount, rows[0].count_title), (Some(35), 3));
+}
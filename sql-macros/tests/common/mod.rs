@@ -0,0 +1,9 @@
+/// Pool of `DATABASE_URL` with tables of `tests/schema.sql`, return None without it and test is
+/// skipped, queries of macros are checked by `.sqlx` then
+pub async fn pool() -> Option<sqlx::PgPool> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    let pool = sqlx::PgPool::connect(&url)
+        .await
+        .expect("Failed to connect to DATABASE_URL");
+    Some(pool)
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use sql_macros::SqlSelectMany;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Settings {
+    pub theme: String,
+}
+
+#[derive(Debug, SqlSelectMany)]
+#[table(name = contains_articles)]
+pub struct Article {
+    pub id: i32,
+    #[table(contains)]
+    pub tags: Vec<String>,
+    #[table(json, contains)]
+    pub settings: Settings,
+}
+
+#[tokio::test]
+async fn select_by_contained_values() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    sqlx::query("DELETE FROM contains_articles")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        r#"INSERT INTO contains_articles (id, tags, settings) VALUES
+            (1, '{rust,sql}', '{"theme": "dark"}'),
+            (2, '{rust}', '{"theme": "light"}')"#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let tags = ["rust".to_string(), "sql".to_string()];
+    let articles = Article::select_many_by_tags_contains(&pool, &tags)
+        .await
+        .unwrap();
+    assert_eq!(articles.iter().map(|a| a.id).collect::<Vec<_>>(), [1]);
+
+    let articles =
+        Article::select_many_by_settings_key(&pool, "theme".to_string(), "light".to_string())
+            .await
+            .unwrap();
+    assert_eq!(articles[0].id, 2);
+    let articles =
+        Article::select_many_by_settings_contains(&pool, serde_json::json!({"theme": "dark"}))
+            .await
+            .unwrap();
+    assert_eq!(articles[0].settings.theme, "dark");
+}
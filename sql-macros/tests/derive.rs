@@ -0,0 +1,8 @@
+//! Derives which don't need database at compile time, queries of `query_as!` are checked by
+//! sqlx against `DATABASE_URL`
+#[test]
+fn derive() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use sql_macros::{OrderBy, Page, Range, SqlFilter, StringFilter};
+
+/// Criterion of enum without `Debug` and `Clone`
+#[derive(PartialEq, sqlx::Type)]
+#[sqlx(type_name = "filter_role", rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Admin,
+}
+
+#[derive(sqlx::FromRow, SqlFilter)]
+#[table(name = filter_users)]
+pub struct User {
+    pub id: i32,
+    pub email: String,
+    pub role: Role,
+    pub score: i32,
+}
+
+fn ids(users: &[User]) -> Vec<i32> {
+    users.iter().map(|user| user.id).collect()
+}
+
+#[tokio::test]
+async fn filter_by_criteria() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    sqlx::query("DELETE FROM filter_users")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO filter_users (id, email, role, score) VALUES
+            (1, 'ann@x.io', 'admin', 10),
+            (2, 'bob@x.io', 'user', 20),
+            (3, 'b_o@y.io', 'user', 30),
+            (4, 'eve@x.io', 'admin', 40)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let all = User::filter(&pool, &UserFilter::default(), &[OrderBy::asc("id")], None)
+        .await
+        .unwrap();
+    assert_eq!(ids(&all), [1, 2, 3, 4]);
+
+    let filter = UserFilter {
+        role: Some(Role::Admin),
+        score: Some(Range::since(20)),
+        ..Default::default()
+    };
+    let admins = User::filter(&pool, &filter, &[], None).await.unwrap();
+    assert_eq!(ids(&admins), [4]);
+    assert!(admins[0].role == Role::Admin);
+
+    // `_` of value is not a wildcard of `LIKE`
+    let filter = UserFilter {
+        email: Some(StringFilter::Contains("b_".to_string())),
+        ..Default::default()
+    };
+    let users = User::filter(&pool, &filter, &[], None).await.unwrap();
+    assert_eq!(ids(&users), [3]);
+
+    let filter = UserFilter {
+        role: Some(Role::User),
+        ..Default::default()
+    };
+    let page = User::filter(
+        &pool,
+        &filter,
+        &[OrderBy::desc("score")],
+        Some(Page::new(2, 1)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids(&page), [2]);
+
+    let unknown = User::filter(&pool, &filter, &[OrderBy::asc("password")], None).await;
+    assert!(matches!(unknown, Err(sqlx::Error::ColumnNotFound(_))));
+}
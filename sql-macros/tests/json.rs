@@ -0,0 +1,123 @@
+mod common;
+
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use sql_macros::{SqlInsert, SqlQueue, SqlSelect, SqlSelectAll, SqlUpdate};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Settings {
+    pub theme: String,
+    pub size: i32,
+}
+
+#[derive(Debug, SqlSelect, SqlSelectAll)]
+#[table(name = json_configs, error = sql_macros::Error)]
+pub struct Config {
+    #[table(primary_key, select)]
+    pub id: i32,
+    #[table(json)]
+    pub settings: Settings,
+}
+
+#[derive(SqlInsert)]
+#[table(name = json_configs)]
+pub struct CreateConfig {
+    #[table(json)]
+    pub settings: Settings,
+}
+
+#[derive(SqlUpdate)]
+#[table(name = json_configs)]
+pub struct UpdateConfig {
+    #[table(update)]
+    pub id: i32,
+    #[table(json)]
+    pub settings: Settings,
+}
+
+#[derive(Debug, SqlQueue)]
+#[table(name = json_jobs, queue(status = status, run_at = run_at, attempts = attempts))]
+pub struct Job {
+    #[table(primary_key)]
+    pub id: i64,
+    #[table(json)]
+    pub payload: Settings,
+    pub status: String,
+    pub attempts: i32,
+}
+
+fn settings(theme: &str) -> Settings {
+    Settings {
+        theme: theme.to_string(),
+        size: 12,
+    }
+}
+
+#[tokio::test]
+async fn json_fields_are_bound_and_decoded() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("DELETE FROM json_configs")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    CreateConfig {
+        settings: settings("dark"),
+    }
+    .insert(&mut conn)
+    .await
+    .unwrap();
+    let configs: Vec<Config> = Config::stream_all(&pool).try_collect().await.unwrap();
+    assert_eq!(configs.len(), 1);
+    let config = &configs[0];
+    assert_eq!(config.settings, settings("dark"));
+
+    UpdateConfig {
+        id: config.id,
+        settings: settings("light"),
+    }
+    .update(&mut conn)
+    .await
+    .unwrap();
+    let config = Config::select_by_id(&pool, config.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(config.settings, settings("light"));
+}
+
+#[tokio::test]
+async fn queue_payload_is_json() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("DELETE FROM json_jobs")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    let job = Job {
+        id: 0,
+        payload: settings("dark"),
+        status: String::new(),
+        attempts: 0,
+    }
+    .enqueue(&mut conn)
+    .await
+    .unwrap();
+    assert_eq!(job.payload, settings("dark"));
+
+    let claimed = Job::claim_batch(&mut conn, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].payload, settings("dark"));
+    claimed[0]
+        .fail_with_retry(&mut conn, Duration::ZERO, 1)
+        .await
+        .unwrap();
+    assert!(Job::claim_batch(&mut conn, 10).await.unwrap().is_empty());
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use sql_macros::{SqlSelect, SqlSelectMany, SqlTable};
+
+#[derive(Debug, sqlx::FromRow, SqlTable)]
+#[table(name = projection_users)]
+pub struct UserEmail {
+    pub id: i32,
+    pub email: String,
+}
+
+#[derive(SqlSelect, SqlSelectMany)]
+#[table(name = projection_users, for_update)]
+#[table(select = email_by_id(id) -> UserEmail)]
+#[table(select_many = emails_by_role(role) -> UserEmail)]
+pub struct User {
+    pub id: i32,
+    pub email: String,
+    pub role: String,
+    pub bio: String,
+}
+
+#[tokio::test]
+async fn select_columns_of_output() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    sqlx::query("DELETE FROM projection_users")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO projection_users (id, email, role, bio) VALUES
+            (1, 'ann@x.io', 'admin', 'a'),
+            (2, 'bob@x.io', 'user', 'b'),
+            (3, 'eve@x.io', 'user', 'e')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let user = User::email_by_id(&pool, 1).await.unwrap().unwrap();
+    assert_eq!(user.email, "ann@x.io");
+    assert!(User::email_by_id(&pool, -1).await.unwrap().is_none());
+    let users = User::emails_by_role(&pool, "user".to_string())
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 2);
+
+    let mut tx = pool.begin().await.unwrap();
+    let user = User::email_by_id_for_update(&mut tx, 2).await.unwrap();
+    assert_eq!(user.unwrap().email, "bob@x.io");
+    let users = User::emails_by_role_for_update(&mut tx, "user".to_string(), 1)
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    tx.rollback().await.unwrap();
+}
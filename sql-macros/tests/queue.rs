@@ -0,0 +1,86 @@
+mod common;
+
+use std::time::Duration;
+
+use sql_macros::SqlQueue;
+
+/// Columns `run_at` and `locked_at` are set by database, they aren't fields
+#[derive(Debug, SqlQueue)]
+#[table(name = queue_jobs, queue(status = status, run_at = run_at, attempts = attempts))]
+pub struct Job {
+    #[table(primary_key)]
+    pub id: i64,
+    pub task: String,
+    pub status: String,
+    pub attempts: i32,
+}
+
+fn job(task: &str) -> Job {
+    Job {
+        id: 0,
+        task: task.to_string(),
+        status: String::new(),
+        attempts: 0,
+    }
+}
+
+async fn status(pool: &sqlx::PgPool, id: i64) -> String {
+    sqlx::query_scalar("SELECT status FROM queue_jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn jobs_are_claimed_retried_and_reaped() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("DELETE FROM queue_jobs")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    let first = job("first").enqueue(&mut conn).await.unwrap();
+    let second = job("second").enqueue(&mut conn).await.unwrap();
+    assert_eq!((first.status.as_str(), first.attempts), ("queued", 0));
+
+    let claimed = Job::claim_batch(&mut conn, 10).await.unwrap();
+    assert_eq!(claimed.len(), 2);
+    assert!(
+        claimed
+            .iter()
+            .all(|job| job.status == "running" && job.attempts == 1)
+    );
+    assert!(Job::claim_batch(&mut conn, 10).await.unwrap().is_empty());
+
+    first.complete(&mut conn).await.unwrap();
+    assert_eq!(status(&pool, first.id).await, "done");
+
+    // Attempts are below max, stuck job is returned to queue
+    let reaped = Job::reap_stuck(&mut conn, Duration::ZERO, 2).await.unwrap();
+    assert_eq!(reaped.rows_affected(), 1);
+    assert_eq!(status(&pool, second.id).await, "queued");
+
+    let claimed = Job::claim_batch(&mut conn, 10).await.unwrap();
+    assert_eq!(claimed[0].attempts, 2);
+    // Job which reached max attempts is failed instead of endless retries
+    Job::reap_stuck(&mut conn, Duration::ZERO, 2).await.unwrap();
+    assert_eq!(status(&pool, second.id).await, "failed");
+
+    let third = job("third").enqueue(&mut conn).await.unwrap();
+    let claimed = Job::claim_batch(&mut conn, 10).await.unwrap();
+    claimed[0]
+        .fail_with_retry(&mut conn, Duration::ZERO, 2)
+        .await
+        .unwrap();
+    assert_eq!(status(&pool, third.id).await, "queued");
+    let claimed = Job::claim_batch(&mut conn, 10).await.unwrap();
+    claimed[0]
+        .fail_with_retry(&mut conn, Duration::ZERO, 2)
+        .await
+        .unwrap();
+    assert_eq!(status(&pool, third.id).await, "failed");
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use sql_macros::{
+    Dialect, OrderBy, SqlDelete, SqlFilter, SqlInsert, SqlSchema, SqlSelect, SqlSelectMany,
+    SqlUpdate, StringFilter,
+};
+
+/// Field `r#type` is column `type` in all queries
+#[derive(
+    Debug, sqlx::FromRow, SqlSchema, SqlInsert, SqlSelect, SqlSelectMany, SqlDelete, SqlFilter,
+)]
+#[table(name = raw_entries)]
+pub struct Entry {
+    #[table(primary_key, select, delete)]
+    pub id: i32,
+    #[table(select_many, delete, index)]
+    pub r#type: String,
+}
+
+#[derive(SqlUpdate)]
+#[table(name = raw_entries)]
+pub struct UpdateEntry {
+    #[table(update)]
+    pub id: i32,
+    pub r#type: String,
+}
+
+#[test]
+fn ddl_has_column_without_prefix() {
+    assert_eq!(
+        Entry::ddl(Dialect::Postgres).unwrap(),
+        [
+            "CREATE TABLE IF NOT EXISTS raw_entries (\n    id INTEGER NOT NULL PRIMARY KEY,\n    type TEXT NOT NULL\n)",
+            "CREATE INDEX IF NOT EXISTS idx_raw_entries_type ON raw_entries (type)",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn queries_use_column_without_prefix() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("DELETE FROM raw_entries")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    for (id, r#type) in [(1, "note"), (2, "note"), (3, "task")] {
+        let entry = Entry {
+            id,
+            r#type: r#type.to_string(),
+        };
+        entry.insert(&mut conn).await.unwrap();
+    }
+    let entry = Entry::select_by_id(&pool, 3).await.unwrap().unwrap();
+    assert_eq!(entry.r#type, "task");
+    let notes = Entry::select_many_by_type(&pool, "note".to_string())
+        .await
+        .unwrap();
+    assert_eq!(notes.len(), 2);
+
+    UpdateEntry {
+        id: 2,
+        r#type: "task".to_string(),
+    }
+    .update(&mut conn)
+    .await
+    .unwrap();
+    let filter = EntryFilter {
+        r#type: Some(StringFilter::Eq("task".to_string())),
+        ..Default::default()
+    };
+    let tasks = Entry::filter(
+        &pool,
+        &filter,
+        &[OrderBy::desc("type"), OrderBy::asc("id")],
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        tasks.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+        [2, 3]
+    );
+
+    Entry::delete_by_type(&mut conn, "note".to_string())
+        .await
+        .unwrap();
+    let deleted = Entry::delete_many_by_type(&mut conn, &["task".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(deleted.rows_affected(), 2);
+}
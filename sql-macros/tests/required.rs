@@ -0,0 +1,83 @@
+mod common;
+
+use sql_macros::{SqlDelete, SqlSelect, SqlUpdate};
+
+#[derive(Debug, SqlSelect, SqlDelete)]
+#[table(name = required_users, required)]
+#[table(select = select_active(is_active))]
+pub struct User {
+    #[table(primary_key, select, delete)]
+    pub id: i32,
+    #[table(unique)]
+    pub email: String,
+    pub is_active: bool,
+}
+
+#[derive(SqlUpdate)]
+#[table(name = required_users, required)]
+pub struct UpdateUser {
+    #[table(update)]
+    pub id: i32,
+    pub email: String,
+}
+
+#[tokio::test]
+async fn missing_row_is_error() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut conn = pool.acquire().await.unwrap();
+    assert!(User::select_by_id(&pool, -1).await.unwrap().is_none());
+    assert!(matches!(
+        User::select_one_by_id(&pool, -1).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+    assert!(matches!(
+        User::select_one_by_email(&pool, "missing@required".to_string()).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+    assert!(matches!(
+        User::delete_one_by_id(&mut conn, -1).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+    let user = UpdateUser {
+        id: -1,
+        email: "missing@required".to_string(),
+    };
+    assert!(matches!(
+        user.update_one(&mut conn).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn existing_row_is_returned() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("DELETE FROM required_users")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    let id: i32 = sqlx::query_scalar(
+        "INSERT INTO required_users (email, is_active) VALUES ('one@required', FALSE) RETURNING id",
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .unwrap();
+
+    let user = User::select_one_by_id(&pool, id).await.unwrap();
+    assert_eq!(user.email, "one@required");
+    let user = User::select_one_active(&pool, false).await.unwrap();
+    assert_eq!(user.id, id);
+    UpdateUser {
+        id,
+        email: "two@required".to_string(),
+    }
+    .update_one(&mut conn)
+    .await
+    .unwrap();
+    let deleted = User::delete_one_by_id(&mut conn, id).await.unwrap();
+    assert_eq!(deleted.rows_affected(), 1);
+}
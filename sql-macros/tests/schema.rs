@@ -0,0 +1,49 @@
+mod common;
+
+use sql_macros::{Dialect, SqlSchema};
+
+#[derive(SqlSchema)]
+#[table(name = schema_accounts)]
+#[table(index(name = idx_schema_accounts_org_email, columns = (org_id, email), unique, where = "deleted_at IS NULL"))]
+pub struct Account {
+    #[table(primary_key)]
+    pub id: i32,
+    #[table(index)]
+    pub org_id: i32,
+    #[table(unique)]
+    pub email: String,
+    pub tags: Vec<String>,
+    pub deleted_at: Option<i64>,
+}
+
+#[tokio::test]
+async fn ddl_is_applied() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let mut tx = pool.begin().await.unwrap();
+    for statement in Account::ddl(Dialect::Postgres).unwrap() {
+        sqlx::query(&statement).execute(&mut *tx).await.unwrap();
+    }
+    let indexes: Vec<String> = sqlx::query_scalar(
+        "SELECT indexname::TEXT FROM pg_indexes WHERE tablename = 'schema_accounts' ORDER BY indexname",
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .unwrap();
+    assert_eq!(
+        indexes,
+        [
+            "idx_schema_accounts_org_email",
+            "idx_schema_accounts_org_id",
+            "schema_accounts_email_key",
+            "schema_accounts_pkey",
+        ]
+    );
+    tx.rollback().await.unwrap();
+}
+
+#[test]
+fn mysql_has_no_partial_index() {
+    assert!(Account::ddl(Dialect::MySql).is_err());
+}
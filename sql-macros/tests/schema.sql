@@ -0,0 +1,71 @@
+-- Tables of tests with database, load them before tests with `DATABASE_URL`:
+--   psql "$DATABASE_URL" -f sql-macros/tests/schema.sql
+-- Cache of queries `.sqlx` is used without `DATABASE_URL`, update it after change of queries:
+--   rm -rf sql-macros/.sqlx && mkdir sql-macros/.sqlx
+--   SQLX_OFFLINE_DIR=$PWD/sql-macros/.sqlx cargo test -p sql-macros --no-run
+
+CREATE TABLE IF NOT EXISTS required_users (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE TABLE IF NOT EXISTS raw_entries (
+    id INTEGER PRIMARY KEY,
+    type TEXT NOT NULL
+);
+
+DO $$ BEGIN
+    CREATE TYPE filter_role AS ENUM ('user', 'admin');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS filter_users (
+    id INTEGER PRIMARY KEY,
+    email TEXT NOT NULL,
+    role filter_role NOT NULL,
+    score INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS contains_articles (
+    id INTEGER PRIMARY KEY,
+    tags TEXT[] NOT NULL,
+    settings JSONB NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS projection_users (
+    id INTEGER PRIMARY KEY,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    bio TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS queue_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    task TEXT NOT NULL,
+    status TEXT NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    attempts INTEGER NOT NULL,
+    locked_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS json_configs (
+    id SERIAL PRIMARY KEY,
+    settings JSONB NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS json_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    attempts INTEGER NOT NULL,
+    locked_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS aggregate_posts (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    amount INTEGER NOT NULL
+);
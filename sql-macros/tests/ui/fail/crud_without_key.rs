@@ -0,0 +1,18 @@
+use sql_macros::{SqlCrud, SqlTable};
+
+#[derive(sqlx::FromRow, SqlTable, SqlCrud)]
+#[table(name = users)]
+pub struct User {
+    pub id: i32,
+    pub email: String,
+}
+
+#[derive(sqlx::FromRow, SqlTable, SqlCrud)]
+#[table(name = projects, tenant = org_id)]
+pub struct Project {
+    #[table(primary_key)]
+    pub id: i32,
+    pub org_id: i32,
+}
+
+fn main() {}
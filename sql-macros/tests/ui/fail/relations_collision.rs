@@ -0,0 +1,16 @@
+use sql_macros::{SqlRelations, SqlTable};
+
+#[derive(sqlx::FromRow, SqlTable)]
+pub struct User {
+    pub id: i32,
+}
+
+#[derive(sqlx::FromRow, SqlTable, SqlRelations)]
+#[table(belongs_to = User, column = user_id)]
+#[table(belongs_to = User, column = user_id)]
+pub struct Post {
+    pub id: i32,
+    pub user_id: i32,
+}
+
+fn main() {}
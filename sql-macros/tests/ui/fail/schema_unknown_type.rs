@@ -0,0 +1,12 @@
+use sql_macros::SqlSchema;
+
+pub struct Money(i64);
+
+#[derive(SqlSchema)]
+pub struct Account {
+    #[table(primary_key)]
+    pub id: i32,
+    pub balance: Money,
+}
+
+fn main() {}
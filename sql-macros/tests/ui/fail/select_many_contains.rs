@@ -0,0 +1,11 @@
+use sql_macros::SqlSelectMany;
+
+#[derive(SqlSelectMany)]
+#[table(name = articles)]
+pub struct Article {
+    pub id: i32,
+    #[table(contains)]
+    pub title: String,
+}
+
+fn main() {}
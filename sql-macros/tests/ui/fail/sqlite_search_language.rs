@@ -0,0 +1,11 @@
+use sql_macros::SqlSearch;
+
+#[derive(SqlSearch)]
+#[table(name = notes, dialect = sqlite)]
+#[table(search = search_notes(body), language = german)]
+pub struct Note {
+    pub id: i64,
+    pub body: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sql_macros::SqlUpdate;
+
+#[derive(SqlUpdate)]
+#[table(name = users)]
+pub struct UpdateUser {
+    pub id: i32,
+    pub email: String,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use sql_macros::{SqlRelations, SqlTable};
+
+#[derive(sqlx::FromRow, SqlTable, SqlRelations)]
+#[table(name = categories, has_many = Entry)]
+#[table(has_many = Person, column = category_id, plural = people)]
+pub struct Category {
+    pub id: i32,
+}
+
+#[derive(sqlx::FromRow, SqlTable, SqlRelations)]
+#[table(name = entries, belongs_to = Category)]
+#[table(belongs_to = Person, column = author_id)]
+pub struct Entry {
+    pub id: i32,
+    pub category_id: Option<i32>,
+    pub author_id: i32,
+}
+
+#[derive(sqlx::FromRow, SqlTable)]
+#[table(name = people)]
+pub struct Person {
+    pub id: i32,
+    pub category_id: Option<i32>,
+}
+
+fn main() {
+    let _ = Category::load_entries;
+    let _ = Category::load_entries_for;
+    let _ = Category::load_people_for;
+    let _ = Entry::load_category_for;
+    let _ = Entry::load_author;
+}
@@ -0,0 +1,31 @@
+use sql_macros::{Dialect, SqlSchema};
+
+pub enum Role {
+    User,
+    Admin,
+}
+
+#[derive(SqlSchema)]
+#[table(name = users)]
+#[table(index(name = idx_users_org_email, columns = (org_id, email), unique))]
+pub struct User {
+    #[table(primary_key)]
+    pub id: i32,
+    pub org_id: i32,
+    #[table(unique)]
+    pub email: String,
+    #[table(sql_type)]
+    pub role: Role,
+    pub nickname: Option<String>,
+}
+
+fn main() {
+    assert_eq!(
+        User::create_table_sql(Dialect::Postgres),
+        "CREATE TABLE IF NOT EXISTS users (\n    id INTEGER NOT NULL PRIMARY KEY,\n    org_id INTEGER NOT NULL,\n    email TEXT NOT NULL UNIQUE,\n    role role NOT NULL,\n    nickname TEXT\n)"
+    );
+    assert_eq!(
+        User::ddl(Dialect::Postgres).unwrap()[1],
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_org_email ON users (org_id, email)"
+    );
+}
@@ -0,0 +1,28 @@
+use sql_macros::{Dialect, SqlSchema};
+
+pub enum Role {
+    User,
+    Admin,
+}
+
+#[derive(SqlSchema)]
+#[table(name = members)]
+pub struct Member {
+    #[table(primary_key)]
+    pub id: i32,
+    #[table(sql_type = "user_role")]
+    pub role: Role,
+    #[table(sql_type = "user_role")]
+    pub granted: Vec<Role>,
+    #[table(sql_type, unique)]
+    pub fallback: Option<Role>,
+    #[table(sql_type = "citext", unique)]
+    pub email: String,
+}
+
+fn main() {
+    assert_eq!(
+        Member::create_table_sql(Dialect::Postgres),
+        "CREATE TABLE IF NOT EXISTS members (\n    id INTEGER NOT NULL PRIMARY KEY,\n    role user_role NOT NULL,\n    granted user_role[] NOT NULL,\n    fallback role UNIQUE,\n    email citext NOT NULL UNIQUE\n)"
+    );
+}
@@ -0,0 +1,29 @@
+use sql_macros::{Query, SqlTable};
+
+#[derive(sqlx::FromRow, SqlTable)]
+#[table(from = posts, join(users, on = "users.id = posts.user_id"))]
+pub struct PostView {
+    pub id: i32,
+    pub org_id: i32,
+    pub r#type: String,
+    #[table(source = users.email)]
+    pub author_email: String,
+}
+
+fn main() {
+    assert_eq!(PostView::from_clause(), "posts JOIN users ON users.id = posts.user_id");
+    assert_eq!(
+        PostView::select_columns(),
+        "posts.id, posts.org_id, posts.type, users.email AS author_email"
+    );
+
+    let query = Query::<PostView>::select()
+        .filter(PostView::AUTHOR_EMAIL.eq("a@x".to_string()))
+        .order_by(PostView::TYPE.desc())
+        .build()
+        .unwrap();
+    assert_eq!(
+        query.sql(),
+        "SELECT posts.id, posts.org_id, posts.type, users.email AS author_email FROM posts JOIN users ON users.id = posts.user_id WHERE users.email = $1 ORDER BY posts.type DESC"
+    );
+}
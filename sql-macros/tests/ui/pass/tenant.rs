@@ -0,0 +1,25 @@
+use sql_macros::{Query, SqlTable};
+
+#[derive(sqlx::FromRow, SqlTable)]
+#[table(from = posts, join(users, on = "users.id = posts.user_id"), tenant = org_id)]
+pub struct PostView {
+    pub id: i32,
+    pub org_id: i32,
+    #[table(source = users.email)]
+    pub author_email: String,
+}
+
+fn main() {
+    assert_eq!(PostView::tenant(), Some("posts.org_id"));
+
+    let query = Query::<PostView>::select()
+        .tenant(1)
+        .filter(PostView::AUTHOR_EMAIL.eq("a@x".to_string()))
+        .build()
+        .unwrap();
+    assert_eq!(
+        query.sql(),
+        "SELECT posts.id, posts.org_id, users.email AS author_email FROM posts JOIN users ON users.id = posts.user_id WHERE posts.org_id = $1 AND users.email = $2"
+    );
+    assert!(Query::<PostView>::select().build().is_err());
+}